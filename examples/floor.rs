@@ -0,0 +1,427 @@
+use std::error::Error;
+use wgpu::util::DeviceExt;
+use wgpu_glyph::{ab_glyph, GlyphBrushBuilder, Layout, Section, Text};
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+// The size of the label on the floor, in pixels
+const LABEL_WIDTH: f32 = 600.0;
+const LABEL_HEIGHT: f32 = 200.0;
+
+// The size of a label pixel in world units
+const PIXEL_SIZE: f32 = 0.005;
+
+const FLOOR_SHADER: &str = r#"
+[[block]]
+struct Globals {
+    transform: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]] var<uniform> globals: Globals;
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+    var x: f32 = f32(i32(index % 2u)) * 4.0 - 2.0;
+    var z: f32 = f32(i32(index / 2u)) * 4.0 - 2.0;
+
+    return globals.transform * vec4<f32>(x, 0.0, z, 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_main() -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(0.3, 0.3, 0.35, 1.0);
+}
+"#;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    // Open window and create a surface
+    let event_loop = winit::event_loop::EventLoop::new();
+
+    let window = winit::window::WindowBuilder::new()
+        .with_resizable(false)
+        .build(&event_loop)
+        .unwrap();
+
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let surface = unsafe { instance.create_surface(&window) };
+
+    // Initialize GPU
+    let (device, queue) = futures::executor::block_on(async {
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .expect("Request adapter");
+
+        adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .expect("Request device")
+    });
+
+    // Create staging belt and a local pool
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut local_pool = futures::executor::LocalPool::new();
+    let local_spawner = local_pool.spawner();
+
+    // Prepare swap chain and depth buffer
+    let size = window.inner_size();
+    let depth_view = create_frame_views(&device, &surface, size);
+
+    // Compute the camera transform
+    let view_projection = multiply(
+        perspective(size.width as f32 / size.height as f32),
+        look_at([0.0, 2.0, 3.0]),
+    );
+
+    // Prepare the floor
+    let floor = Floor::new(&device, view_projection);
+
+    // Prepare glyph_brush
+    let inconsolata = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "Inconsolata-Regular.ttf"
+    ))?;
+
+    // Pull the label towards the camera, so it lies on top of the floor
+    // even if both are coplanar.
+    let mut glyph_brush = GlyphBrushBuilder::using_font(inconsolata)
+        .depth_stencil_state(wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        })
+        .depth_bias(wgpu::DepthBiasState {
+            constant: -2,
+            slope_scale: -2.0,
+            clamp: 0.0,
+        })
+        .build(&device, FORMAT);
+
+    // Lay the label flat on the floor, centered at the origin
+    #[rustfmt::skip]
+    let label_to_floor = [
+        PIXEL_SIZE, 0.0, 0.0, 0.0,
+        0.0, 0.0, PIXEL_SIZE, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        -LABEL_WIDTH / 2.0 * PIXEL_SIZE, 0.0, -LABEL_HEIGHT / 2.0 * PIXEL_SIZE, 1.0,
+    ];
+
+    let transform = multiply(view_projection, label_to_floor);
+
+    // Render loop
+    window.request_redraw();
+
+    event_loop.run(move |event, _, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => *control_flow = winit::event_loop::ControlFlow::Exit,
+            winit::event::Event::RedrawRequested { .. } => {
+                // Get a command encoder for the current frame
+                let mut encoder = device.create_command_encoder(
+                    &wgpu::CommandEncoderDescriptor {
+                        label: Some("Redraw"),
+                    },
+                );
+
+                // Get the next frame
+                let frame =
+                    surface.get_current_texture().expect("Get next frame");
+                let view = &frame
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+
+                // Clear frame and draw the floor
+                {
+                    let mut render_pass = encoder.begin_render_pass(
+                        &wgpu::RenderPassDescriptor {
+                            label: Some("Floor render pass"),
+                            color_attachments: &[
+                                wgpu::RenderPassColorAttachment {
+                                    view,
+                                    resolve_target: None,
+                                    ops: wgpu::Operations {
+                                        load: wgpu::LoadOp::Clear(
+                                            wgpu::Color {
+                                                r: 0.1,
+                                                g: 0.1,
+                                                b: 0.15,
+                                                a: 1.0,
+                                            },
+                                        ),
+                                        store: true,
+                                    },
+                                },
+                            ],
+                            depth_stencil_attachment: Some(
+                                wgpu::RenderPassDepthStencilAttachment {
+                                    view: &depth_view,
+                                    depth_ops: Some(wgpu::Operations {
+                                        load: wgpu::LoadOp::Clear(1.0),
+                                        store: true,
+                                    }),
+                                    stencil_ops: None,
+                                },
+                            ),
+                        },
+                    );
+
+                    floor.draw(&mut render_pass);
+                }
+
+                glyph_brush.queue(Section {
+                    screen_position: (LABEL_WIDTH / 2.0, LABEL_HEIGHT / 2.0),
+                    bounds: (LABEL_WIDTH, LABEL_HEIGHT),
+                    text: vec![Text::new("Floor label")
+                        .with_color([0.9, 0.9, 0.9, 1.0])
+                        .with_scale(100.0)],
+                    layout: Layout::default_single_line()
+                        .h_align(wgpu_glyph::HorizontalAlign::Center)
+                        .v_align(wgpu_glyph::VerticalAlign::Center),
+                });
+
+                // Draw the label on top of the floor
+                glyph_brush
+                    .draw_queued_with_transform(
+                        &device,
+                        &mut staging_belt,
+                        &mut encoder,
+                        view,
+                        wgpu::RenderPassDepthStencilAttachment {
+                            view: &depth_view,
+                            depth_ops: Some(wgpu::Operations {
+                                load: wgpu::LoadOp::Load,
+                                store: true,
+                            }),
+                            stencil_ops: None,
+                        },
+                        transform,
+                    )
+                    .expect("Draw queued");
+
+                // Submit the work!
+                staging_belt.finish();
+                queue.submit(Some(encoder.finish()));
+                frame.present();
+
+                // Recall unused staging buffers
+                use futures::task::SpawnExt;
+
+                local_spawner
+                    .spawn(staging_belt.recall())
+                    .expect("Recall staging belt");
+
+                local_pool.run_until_stalled();
+            }
+            _ => {
+                *control_flow = winit::event_loop::ControlFlow::Wait;
+            }
+        }
+    })
+}
+
+struct Floor {
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Floor {
+    fn new(device: &wgpu::Device, transform: [f32; 16]) -> Floor {
+        let shader =
+            device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+                label: Some("Floor shader"),
+                source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(
+                    FLOOR_SHADER,
+                )),
+            });
+
+        let buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Floor transform"),
+                contents: bytemuck::cast_slice(&transform),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+        let layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Floor bind group layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Floor bind group"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        let pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Floor pipeline layout"),
+                bind_group_layouts: &[&layout],
+                push_constant_ranges: &[],
+            });
+
+        let pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Floor pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[wgpu::ColorTargetState {
+                        format: FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }],
+                }),
+            });
+
+        Floor {
+            pipeline,
+            bind_group,
+        }
+    }
+
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..4, 0..1);
+    }
+}
+
+fn create_frame_views(
+    device: &wgpu::Device,
+    surface: &wgpu::Surface,
+    size: winit::dpi::PhysicalSize<u32>,
+) -> wgpu::TextureView {
+    let (width, height) = (size.width, size.height);
+
+    surface.configure(
+        device,
+        &wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: FORMAT,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Mailbox,
+        },
+    );
+
+    let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Depth buffer"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+    });
+
+    depth_texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// Multiplies two column-major 4x4 matrices.
+fn multiply(a: [f32; 16], b: [f32; 16]) -> [f32; 16] {
+    let mut result = [0.0; 16];
+
+    for column in 0..4 {
+        for row in 0..4 {
+            result[column * 4 + row] =
+                (0..4).map(|i| a[i * 4 + row] * b[column * 4 + i]).sum();
+        }
+    }
+
+    result
+}
+
+/// A perspective projection with a vertical field of view of 45 degrees.
+fn perspective(aspect_ratio: f32) -> [f32; 16] {
+    let (near, far) = (0.1, 100.0);
+    let f = 1.0 / (std::f32::consts::FRAC_PI_8).tan();
+
+    #[rustfmt::skip]
+    let projection = [
+        f / aspect_ratio, 0.0, 0.0, 0.0,
+        0.0, f, 0.0, 0.0,
+        0.0, 0.0, far / (near - far), -1.0,
+        0.0, 0.0, near * far / (near - far), 0.0,
+    ];
+
+    projection
+}
+
+/// A view from the given `eye` position looking at the origin.
+fn look_at(eye: [f32; 3]) -> [f32; 16] {
+    let normalize = |v: [f32; 3]| {
+        let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+
+        [v[0] / length, v[1] / length, v[2] / length]
+    };
+
+    let cross = |a: [f32; 3], b: [f32; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+
+    let dot =
+        |a: [f32; 3], b: [f32; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+    let forward = normalize([-eye[0], -eye[1], -eye[2]]);
+    let right = normalize(cross(forward, [0.0, 1.0, 0.0]));
+    let up = cross(right, forward);
+
+    #[rustfmt::skip]
+    let view = [
+        right[0], up[0], -forward[0], 0.0,
+        right[1], up[1], -forward[1], 0.0,
+        right[2], up[2], -forward[2], 0.0,
+        -dot(right, eye), -dot(up, eye), dot(forward, eye), 1.0,
+    ];
+
+    view
+}
@@ -0,0 +1,206 @@
+use std::error::Error;
+use wgpu_glyph::{ab_glyph, GlyphBrushBuilder, Region, Section, Text};
+
+const VIEWPORT: Region = Region {
+    x: 50,
+    y: 50,
+    width: 400,
+    height: 300,
+};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    // Open window and create a surface
+    let event_loop = winit::event_loop::EventLoop::new();
+
+    let window = winit::window::WindowBuilder::new()
+        .with_resizable(false)
+        .build(&event_loop)
+        .unwrap();
+
+    let instance = wgpu::Instance::new(wgpu::Backends::all());
+    let surface = unsafe { instance.create_surface(&window) };
+
+    // Initialize GPU
+    let (device, queue) = futures::executor::block_on(async {
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .expect("Request adapter");
+
+        adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .expect("Request device")
+    });
+
+    // Create staging belt and a local pool
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut local_pool = futures::executor::LocalPool::new();
+    let local_spawner = local_pool.spawner();
+
+    // Prepare swap chain
+    let render_format = wgpu::TextureFormat::Bgra8UnormSrgb;
+    let mut size = window.inner_size();
+
+    surface.configure(
+        &device,
+        &wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: render_format,
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Mailbox,
+        },
+    );
+
+    // Prepare glyph_brush
+    let inconsolata = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "Inconsolata-Regular.ttf"
+    ))?;
+
+    let mut glyph_brush = GlyphBrushBuilder::using_font(inconsolata)
+        .build(&device, render_format);
+
+    // The text is laid out once, scrolling only changes the transform
+    let lipsum = include_str!("lipsum.txt");
+    let mut scroll = 0.0;
+
+    // Render loop
+    window.request_redraw();
+
+    event_loop.run(move |event, _, control_flow| {
+        match event {
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => *control_flow = winit::event_loop::ControlFlow::Exit,
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::Resized(new_size),
+                ..
+            } => {
+                size = new_size;
+
+                surface.configure(
+                    &device,
+                    &wgpu::SurfaceConfiguration {
+                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                        format: render_format,
+                        width: size.width,
+                        height: size.height,
+                        present_mode: wgpu::PresentMode::Mailbox,
+                    },
+                );
+            }
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => {
+                let delta = match delta {
+                    winit::event::MouseScrollDelta::LineDelta(_, y) => y * 20.0,
+                    winit::event::MouseScrollDelta::PixelDelta(position) => {
+                        position.y as f32
+                    }
+                };
+
+                scroll = f32::max(0.0, scroll - delta);
+
+                window.request_redraw();
+            }
+            winit::event::Event::RedrawRequested { .. } => {
+                // Get a command encoder for the current frame
+                let mut encoder = device.create_command_encoder(
+                    &wgpu::CommandEncoderDescriptor {
+                        label: Some("Redraw"),
+                    },
+                );
+
+                // Get the next frame
+                let frame =
+                    surface.get_current_texture().expect("Get next frame");
+                let view = &frame
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+
+                // Clear frame
+                {
+                    let _ = encoder.begin_render_pass(
+                        &wgpu::RenderPassDescriptor {
+                            label: Some("Render pass"),
+                            color_attachments: &[
+                                wgpu::RenderPassColorAttachment {
+                                    view,
+                                    resolve_target: None,
+                                    ops: wgpu::Operations {
+                                        load: wgpu::LoadOp::Clear(
+                                            wgpu::Color {
+                                                r: 0.4,
+                                                g: 0.4,
+                                                b: 0.4,
+                                                a: 1.0,
+                                            },
+                                        ),
+                                        store: true,
+                                    },
+                                },
+                            ],
+                            depth_stencil_attachment: None,
+                        },
+                    );
+                }
+
+                // The section is not bounded vertically, so the scissoring
+                // region is the only thing clipping it
+                glyph_brush.queue(Section {
+                    screen_position: (VIEWPORT.x as f32, VIEWPORT.y as f32),
+                    bounds: (VIEWPORT.width as f32, f32::INFINITY),
+                    text: vec![Text::new(lipsum)
+                        .with_color([1.0, 1.0, 1.0, 1.0])
+                        .with_scale(20.0)],
+                    ..Section::default()
+                });
+
+                // Translate the text up by the scroll offset
+                let mut transform = wgpu_glyph::orthographic_projection(
+                    size.width,
+                    size.height,
+                );
+
+                transform[13] += 2.0 * scroll / size.height as f32;
+
+                // Draw the text!
+                glyph_brush
+                    .draw_queued_with_transform_and_scissoring(
+                        &device,
+                        &mut staging_belt,
+                        &mut encoder,
+                        view,
+                        transform,
+                        VIEWPORT,
+                    )
+                    .expect("Draw queued");
+
+                // Submit the work!
+                staging_belt.finish();
+                queue.submit(Some(encoder.finish()));
+                frame.present();
+                // Recall unused staging buffers
+                use futures::task::SpawnExt;
+
+                local_spawner
+                    .spawn(staging_belt.recall())
+                    .expect("Recall staging belt");
+
+                local_pool.run_until_stalled();
+            }
+            _ => {
+                *control_flow = winit::event_loop::ControlFlow::Wait;
+            }
+        }
+    })
+}
@@ -0,0 +1,71 @@
+use crate::origin::Origin;
+
+use glyph_brush::ab_glyph::Rect;
+use glyph_brush::{HorizontalAlign, Layout, Section, VerticalAlign};
+
+/// The horizontal alignment of the text of a section within a rectangle,
+/// used by [`queue_aligned`](struct.GlyphBrush.html#method.queue_aligned).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alignment {
+    /// Aligns every line to the left edge of the rectangle.
+    Left,
+    /// Centers every line horizontally in the rectangle.
+    Center,
+    /// Aligns every line to the right edge of the rectangle.
+    Right,
+    /// Aligns every line to the left edge and stretches the whitespace of
+    /// wrapped lines to fill the width of the rectangle, like
+    /// [`Style::justify`](struct.Style.html#structfield.justify).
+    Justified,
+}
+
+impl Alignment {
+    /// Positions and bounds the section so its text is laid out within the
+    /// given rectangle, in the space of the given [`Origin`], keeping its
+    /// vertical alignment.
+    pub(crate) fn apply(
+        self,
+        section: &mut Section<'_>,
+        rect: Rect,
+        origin: Origin,
+    ) {
+        let rect = origin.rect(rect);
+
+        let h_align = match self {
+            Alignment::Left | Alignment::Justified => HorizontalAlign::Left,
+            Alignment::Center => HorizontalAlign::Center,
+            Alignment::Right => HorizontalAlign::Right,
+        };
+
+        let v_align = match &mut section.layout {
+            Layout::SingleLine {
+                h_align: align,
+                v_align,
+                ..
+            }
+            | Layout::Wrap {
+                h_align: align,
+                v_align,
+                ..
+            } => {
+                *align = h_align;
+                *v_align
+            }
+        };
+
+        let x = match h_align {
+            HorizontalAlign::Left => rect.min.x,
+            HorizontalAlign::Center => (rect.min.x + rect.max.x) / 2.0,
+            HorizontalAlign::Right => rect.max.x,
+        };
+
+        let y = match v_align {
+            VerticalAlign::Top => rect.min.y,
+            VerticalAlign::Center => (rect.min.y + rect.max.y) / 2.0,
+            VerticalAlign::Bottom => rect.max.y,
+        };
+
+        section.screen_position = origin.position((x, y));
+        section.bounds = (rect.width(), rect.height());
+    }
+}
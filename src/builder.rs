@@ -4,22 +4,110 @@ use glyph_brush::ab_glyph::Font;
 use glyph_brush::delegate_glyph_brush_builder_fns;
 use glyph_brush::DefaultSectionHasher;
 
+use super::pipeline::{Instance, Topology};
 use super::GlyphBrush;
+use super::{ColorSpace, CustomShader, GrowthPolicy, Origin};
 
 /// Builder for a [`GlyphBrush`](struct.GlyphBrush.html).
+///
+/// Besides its own options, it forwards the caching options of `glyph_brush`,
+/// like [`draw_cache_scale_tolerance`](#method.draw_cache_scale_tolerance) and
+/// [`draw_cache_position_tolerance`](#method.draw_cache_position_tolerance).
+/// Lower tolerances produce sharper results at the cost of more glyph cache
+/// entries.
 pub struct GlyphBrushBuilder<D, F, H = DefaultSectionHasher> {
     inner: glyph_brush::GlyphBrushBuilder<F, H>,
-    texture_filter_method: wgpu::FilterMode,
+    settings: Settings,
+    uniform_layout: Option<wgpu::BindGroupLayout>,
     depth: D,
 }
 
+/// The configuration of a [`GlyphBrush`] collected by a [`GlyphBrushBuilder`].
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub texture_filter_method: wgpu::FilterMode,
+    pub cache_format: wgpu::TextureFormat,
+    pub crop_to_bounds: bool,
+    pub premultiplied_output: bool,
+    pub alpha_cutoff: Option<f32>,
+    pub antialiasing: bool,
+    pub gamma: f32,
+    pub edge_softness: f32,
+    pub flip_y: bool,
+    pub origin: Origin,
+    pub pixel_snapping: bool,
+    pub instance_capacity: usize,
+    pub max_instances: Option<usize>,
+    pub skip_identical_instances: bool,
+    pub tab_width: Option<usize>,
+    pub cache_growth: bool,
+    pub max_cache_resizes: Option<usize>,
+    pub growth_policy: GrowthPolicy,
+    pub upload_budget: Option<usize>,
+    pub topology: Topology,
+    pub front_face: wgpu::FrontFace,
+    pub cull_mode: Option<wgpu::Face>,
+    pub load_op: wgpu::LoadOp<wgpu::Color>,
+    pub color_space: ColorSpace,
+    pub shader: Option<CustomShader>,
+    pub default_color: [f32; 4],
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            texture_filter_method: wgpu::FilterMode::Linear,
+            cache_format: wgpu::TextureFormat::R8Unorm,
+            crop_to_bounds: true,
+            premultiplied_output: false,
+            alpha_cutoff: None,
+            antialiasing: true,
+            gamma: 1.0,
+            edge_softness: 1.0,
+            flip_y: false,
+            origin: Origin::default(),
+            pixel_snapping: false,
+            instance_capacity: Instance::INITIAL_AMOUNT,
+            max_instances: None,
+            skip_identical_instances: false,
+            tab_width: None,
+            cache_growth: true,
+            max_cache_resizes: None,
+            growth_policy: GrowthPolicy::default(),
+            upload_budget: None,
+            topology: Topology::default(),
+            front_face: wgpu::FrontFace::Cw,
+            cull_mode: None,
+            load_op: wgpu::LoadOp::Load,
+            color_space: ColorSpace::default(),
+            shader: None,
+            default_color: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl Settings {
+    /// Converts the position of a section into the space text is laid out
+    /// in, snapping it to whole pixels if enabled.
+    pub(crate) fn screen_position(&self, position: (f32, f32)) -> (f32, f32) {
+        let (x, y) = self.origin.position(position);
+
+        if self.pixel_snapping {
+            (x.round(), y.round())
+        } else {
+            (x, y)
+        }
+    }
+}
+
 impl<F, H> From<glyph_brush::GlyphBrushBuilder<F, H>>
     for GlyphBrushBuilder<(), F, H>
 {
     fn from(inner: glyph_brush::GlyphBrushBuilder<F, H>) -> Self {
         GlyphBrushBuilder {
             inner,
-            texture_filter_method: wgpu::FilterMode::Linear,
+            settings: Settings::default(),
+            uniform_layout: None,
             depth: (),
         }
     }
@@ -36,7 +124,8 @@ impl GlyphBrushBuilder<(), ()> {
     pub fn using_fonts<F: Font>(fonts: Vec<F>) -> GlyphBrushBuilder<(), F> {
         GlyphBrushBuilder {
             inner: glyph_brush::GlyphBrushBuilder::using_fonts(fonts),
-            texture_filter_method: wgpu::FilterMode::Linear,
+            settings: Settings::default(),
+            uniform_layout: None,
             depth: (),
         }
     }
@@ -67,7 +156,336 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
         mut self,
         filter_method: wgpu::FilterMode,
     ) -> Self {
-        self.texture_filter_method = filter_method;
+        self.settings.texture_filter_method = filter_method;
+        self
+    }
+
+    /// Sets the texture format of the glyph cache.
+    ///
+    /// Glyphs are uploaded to the cache as a single byte of coverage per
+    /// texel, which the fragment shader samples from the red channel.
+    /// Therefore, the format must be an 8-bit, single-channel, float-sampled
+    /// format.
+    ///
+    /// Defaults to `R8Unorm`.
+    pub fn cache_format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.settings.cache_format = format;
+        self
+    }
+
+    /// Sets whether glyphs should be cropped to the `bounds` of their section.
+    ///
+    /// When disabled, glyphs overflowing their section bounds are drawn in
+    /// full.
+    ///
+    /// By default, this feature is __enabled__.
+    pub fn crop_to_bounds(mut self, crop_to_bounds: bool) -> Self {
+        self.settings.crop_to_bounds = crop_to_bounds;
+        self
+    }
+
+    /// Sets whether the rendered text should be output with premultiplied
+    /// alpha.
+    ///
+    /// Enable this when rendering to a texture that is composited later on
+    /// with premultiplied blending. Otherwise, the straight alpha output
+    /// produces dark fringes around the glyphs.
+    ///
+    /// By default, this feature is __disabled__.
+    pub fn premultiplied_output(mut self, premultiplied_output: bool) -> Self {
+        self.settings.premultiplied_output = premultiplied_output;
+        self
+    }
+
+    /// Sets whether the Y axis of the clip space coordinates should be flipped
+    /// after applying the transform.
+    ///
+    /// This is useful when the target uses a Y axis convention in normalized
+    /// device coordinates opposite to the one expected by the provided
+    /// transforms (e.g. [`orthographic_projection`]), which would otherwise
+    /// render the text upside down.
+    ///
+    /// By default, this feature is __disabled__.
+    ///
+    /// [`orthographic_projection`]: fn.orthographic_projection.html
+    pub fn flip_y(mut self, flip_y: bool) -> Self {
+        self.settings.flip_y = flip_y;
+        self
+    }
+
+    /// Sets the corner of the render target where the coordinates used to
+    /// position sections start, and the direction of their y axis.
+    ///
+    /// With [`Origin::BottomLeft`](enum.Origin.html#variant.BottomLeft),
+    /// transforms have to map a y axis pointing up as well. Text is drawn
+    /// upright with either origin.
+    ///
+    /// Defaults to [`Origin::TopLeft`](enum.Origin.html).
+    pub fn origin(mut self, origin: Origin) -> Self {
+        self.settings.origin = origin;
+        self
+    }
+
+    /// Sets whether the position of every section should be snapped to whole
+    /// pixels, so text scrolled by fractions of a pixel stays crisp instead of
+    /// shimmering, at the cost of moving in steps.
+    ///
+    /// The glyphs of a section, and the bounds they are cropped to, move
+    /// together, so they never jitter relative to each other. Glyphs queued
+    /// with [`queue_pre_positioned`](struct.GlyphBrush.html#method.queue_pre_positioned)
+    /// are not snapped.
+    ///
+    /// By default, this feature is __disabled__.
+    pub fn pixel_snapping(mut self, pixel_snapping: bool) -> Self {
+        self.settings.pixel_snapping = pixel_snapping;
+        self
+    }
+
+    /// Sets the amount of glyph instances the GPU buffer is initially able to
+    /// hold.
+    ///
+    /// The buffer is reallocated on demand if more instances are needed.
+    /// Lowering this value reduces the memory reserved up front.
+    ///
+    /// Defaults to `50_000`.
+    pub fn initial_instance_capacity(mut self, capacity: usize) -> Self {
+        self.settings.instance_capacity = capacity;
+        self
+    }
+
+    /// Sets the maximum amount of instances drawn at once, including glyphs,
+    /// backgrounds, rectangles and decorations.
+    ///
+    /// The GPU buffer is never reallocated beyond this amount, and it is
+    /// initially sized to hold at most this amount. Draws with more instances
+    /// return an error and draw nothing instead.
+    ///
+    /// By default, there is no maximum.
+    pub fn max_instances(mut self, max_instances: usize) -> Self {
+        self.settings.max_instances = Some(max_instances);
+        self
+    }
+
+    /// Expands tabs into spaces, aligning them to tab stops placed every
+    /// `tab_width` characters.
+    ///
+    /// Tab stops are computed in characters, which means they will only line
+    /// up perfectly when using a monospace font.
+    ///
+    /// By default, tabs are left untouched.
+    pub fn tab_width(mut self, tab_width: usize) -> Self {
+        self.settings.tab_width = Some(tab_width);
+        self
+    }
+
+    /// Sets whether the instances given to
+    /// [`draw_instances`](struct.GlyphBrush.html#method.draw_instances) are
+    /// compared with the ones of the previous call, skipping their upload if
+    /// they are identical.
+    ///
+    /// The comparison costs a copy and a scan of the instances on every call,
+    /// so it only pays off when they rarely change, e.g. in an idle editor.
+    ///
+    /// Defaults to `false`.
+    pub fn skip_identical_instances(
+        mut self,
+        skip_identical_instances: bool,
+    ) -> Self {
+        self.settings.skip_identical_instances = skip_identical_instances;
+        self
+    }
+
+    /// Sets a coverage threshold under which fragments are discarded, while
+    /// the rest are drawn fully opaque, producing hard edges without any
+    /// antialiasing (e.g. to use the text as a stencil mask).
+    ///
+    /// By default, edges are smooth.
+    pub fn alpha_cutoff(mut self, alpha_cutoff: f32) -> Self {
+        self.settings.alpha_cutoff = Some(alpha_cutoff);
+        self
+    }
+
+    /// Sets whether glyphs are rasterized with antialiasing.
+    ///
+    /// When disabled, the coverage of every pixel of a glyph is rounded to
+    /// fully transparent or fully opaque before it is uploaded to the glyph
+    /// cache. Combined with `wgpu::FilterMode::Nearest` in
+    /// [`texture_filter_method`](#method.texture_filter_method) and text
+    /// drawn at whole pixels, e.g. with
+    /// [`pixel_snapping`](#method.pixel_snapping), this keeps small pixel
+    /// fonts crisp. Unlike an [`alpha_cutoff`](#method.alpha_cutoff), which
+    /// applies to the sampled coverage, the glyph cache itself only holds
+    /// hard edges.
+    ///
+    /// Defaults to `true`.
+    pub fn antialiasing(mut self, antialiasing: bool) -> Self {
+        self.settings.antialiasing = antialiasing;
+        self
+    }
+
+    /// Sets the gamma applied to the coverage of the glyphs, raising it to the
+    /// power of `gamma`, to tune how heavy antialiased text looks on a given
+    /// display.
+    ///
+    /// Values above `1.0` make the text thinner and values below `1.0` make
+    /// it bolder.
+    ///
+    /// Defaults to `1.0`, which keeps the coverage as it is.
+    pub fn gamma(mut self, gamma: f32) -> Self {
+        self.settings.gamma = gamma;
+        self
+    }
+
+    /// Sets the softness of the edges of the glyphs, remapping their coverage
+    /// with an S-shaped curve that keeps it fully transparent and fully
+    /// opaque at the ends.
+    ///
+    /// Values below `1.0` sharpen the edges by increasing their contrast, and
+    /// values above `1.0` soften them. Unlike the [`gamma`](#method.gamma),
+    /// which shifts all the coverage, half covered pixels are left as they
+    /// are.
+    ///
+    /// Defaults to `1.0`, which keeps the coverage as it is.
+    pub fn edge_softness(mut self, edge_softness: f32) -> Self {
+        self.settings.edge_softness = edge_softness;
+        self
+    }
+
+    /// Sets the color of the sections queued with
+    /// [`GlyphBrush::queue_with_default_color`](struct.GlyphBrush.html#method.queue_with_default_color),
+    /// e.g. the foreground color of a theme.
+    ///
+    /// Defaults to opaque white.
+    pub fn default_color(mut self, color: [f32; 4]) -> Self {
+        self.settings.default_color = color;
+        self
+    }
+
+    /// Sets the primitive topology used to draw the quad of every glyph.
+    ///
+    /// An indexed triangle list can be useful for tooling or drivers that do
+    /// not deal well with triangle strips.
+    ///
+    /// Defaults to [`Topology::TriangleStrip`](enum.Topology.html).
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.settings.topology = topology;
+        self
+    }
+
+    /// Sets the color space of the colors of sections and instances, which are
+    /// converted to the space of the render target when drawing.
+    ///
+    /// Defaults to [`ColorSpace::Srgb`](enum.ColorSpace.html).
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.settings.color_space = color_space;
+        self
+    }
+
+    /// Sets how the render target is loaded at the start of every render pass
+    /// used to draw glyphs.
+    ///
+    /// Clearing is mostly useful when the text is drawn into a target of its
+    /// own, e.g. an offscreen texture composited later. The whole target is
+    /// cleared, even when drawing into a [`Region`](struct.Region.html).
+    ///
+    /// Defaults to `wgpu::LoadOp::Load`, which keeps the existing contents of
+    /// the target.
+    pub fn load_op(mut self, load_op: wgpu::LoadOp<wgpu::Color>) -> Self {
+        self.settings.load_op = load_op;
+        self
+    }
+
+    /// Sets the winding order of the glyph quads considered to face the
+    /// camera, which matters when culling them.
+    ///
+    /// With an [`orthographic_projection`](fn.orthographic_projection.html),
+    /// glyph quads are wound counter-clockwise on the render target.
+    ///
+    /// Defaults to `wgpu::FrontFace::Cw`.
+    pub fn front_face(mut self, front_face: wgpu::FrontFace) -> Self {
+        self.settings.front_face = front_face;
+        self
+    }
+
+    /// Sets the faces of the glyph quads to cull, e.g. to hide labels placed
+    /// in a 3D scene when seen from behind.
+    ///
+    /// Defaults to `None`, which draws both faces.
+    pub fn cull_mode(mut self, cull_mode: Option<wgpu::Face>) -> Self {
+        self.settings.cull_mode = cull_mode;
+        self
+    }
+
+    /// Sets whether the glyph cache can grow when the queued glyphs do not fit
+    /// in it.
+    ///
+    /// When disabled, drawing fails with an error instead of allocating a
+    /// bigger cache texture. The queued sections are kept in that case, like
+    /// `glyph_brush` does, so less text should be queued before drawing again.
+    ///
+    /// Defaults to `true`.
+    pub fn cache_growth(mut self, cache_growth: bool) -> Self {
+        self.settings.cache_growth = cache_growth;
+        self
+    }
+
+    /// Sets the maximum amount of times the glyph cache can grow while
+    /// processing the queue once, before drawing fails with an error.
+    ///
+    /// Every time the queued glyphs do not fit, the cache is resized to the
+    /// size suggested by `glyph_brush` and the queue is processed again.
+    ///
+    /// By default, the cache grows as many times as needed.
+    pub fn max_cache_resizes(mut self, max_cache_resizes: usize) -> Self {
+        self.settings.max_cache_resizes = Some(max_cache_resizes);
+        self
+    }
+
+    /// Sets the policy used to choose the next size of the glyph cache when
+    /// it grows.
+    ///
+    /// Defaults to [`GrowthPolicy::Suggested`](enum.GrowthPolicy.html).
+    pub fn growth_policy(mut self, growth_policy: GrowthPolicy) -> Self {
+        self.settings.growth_policy = growth_policy;
+        self
+    }
+
+    /// Sets the maximum amount of glyphs uploaded to the glyph cache per draw
+    /// call, to spread the uploads of many new glyphs over several frames.
+    ///
+    /// Glyphs waiting to be uploaded are not drawn, and the following draw
+    /// calls keep uploading them, even if the queue does not change, until
+    /// all of them are drawn.
+    /// [`needs_redraw`](struct.GlyphBrush.html#method.needs_redraw) stays
+    /// `true` meanwhile. A budget of `0` is treated as `1`, so that all the
+    /// glyphs are eventually uploaded.
+    ///
+    /// By default, all the glyphs are uploaded right away.
+    pub fn upload_budget(mut self, glyphs_per_frame: usize) -> Self {
+        self.settings.upload_budget = Some(glyphs_per_frame.max(1));
+        self
+    }
+
+    /// Sets a WGSL shader of your own, with its entry points, replacing the
+    /// built-in one.
+    ///
+    /// The entry points are only checked by the validation of `wgpu` when the
+    /// brush is built, which reports any missing entry point or any mismatch
+    /// with the instances and the bind group of the pipeline.
+    pub fn shader(mut self, shader: CustomShader) -> Self {
+        self.settings.shader = Some(shader);
+        self
+    }
+
+    /// Sets the bind group layout used by the pipeline, instead of creating
+    /// one of its own, e.g. to share it with other pipelines of your renderer.
+    ///
+    /// The layout must be created with the entries returned by
+    /// [`uniform_layout_entries`](fn.uniform_layout_entries.html), which is
+    /// not checked here. A layout that does not match is only caught by the
+    /// validation of `wgpu` when the brush is built.
+    pub fn uniform_layout(mut self, layout: wgpu::BindGroupLayout) -> Self {
+        self.uniform_layout = Some(layout);
         self
     }
 
@@ -84,7 +502,8 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
     ) -> GlyphBrushBuilder<D, F, T> {
         GlyphBrushBuilder {
             inner: self.inner.section_hasher(section_hasher),
-            texture_filter_method: self.texture_filter_method,
+            settings: self.settings,
+            uniform_layout: self.uniform_layout,
             depth: self.depth,
         }
     }
@@ -96,10 +515,31 @@ impl<F: Font, D, H: BuildHasher> GlyphBrushBuilder<D, F, H> {
     ) -> GlyphBrushBuilder<wgpu::DepthStencilState, F, H> {
         GlyphBrushBuilder {
             inner: self.inner,
-            texture_filter_method: self.texture_filter_method,
+            settings: self.settings,
+            uniform_layout: self.uniform_layout,
             depth: depth_stencil_state,
         }
     }
+
+    /// Sets a depth stencil state testing and writing depth in the given
+    /// `format`, using `wgpu::CompareFunction::LessEqual` so glyphs drawn at
+    /// the same depth do not hide each other.
+    ///
+    /// The test and the write can then be configured independently with
+    /// [`depth_compare`](#method.depth_compare) and
+    /// [`depth_write_enabled`](#method.depth_write_enabled).
+    pub fn depth_format(
+        self,
+        format: wgpu::TextureFormat,
+    ) -> GlyphBrushBuilder<wgpu::DepthStencilState, F, H> {
+        self.depth_stencil_state(wgpu::DepthStencilState {
+            format,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        })
+    }
 }
 
 impl<F: Font + Sync, H: BuildHasher> GlyphBrushBuilder<(), F, H> {
@@ -112,16 +552,72 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrushBuilder<(), F, H> {
     ) -> GlyphBrush<(), F, H> {
         GlyphBrush::<(), F, H>::new(
             device,
-            self.texture_filter_method,
             render_format,
+            self.settings,
+            self.uniform_layout,
             self.inner,
         )
     }
+
+    /// Builds a `GlyphBrush` like [`build`](#method.build), but returns an
+    /// error instead of panicking if it cannot be created.
+    ///
+    /// The [`cache_format`](#method.cache_format) and the custom
+    /// [`shader`](#method.shader), if any, are validated before creating the
+    /// pipeline. The [`uniform_layout`](#method.uniform_layout) cannot be
+    /// inspected, so its errors are still reported to the error handler of
+    /// the `device` like with `build`.
+    pub fn try_build(
+        self,
+        device: &wgpu::Device,
+        render_format: wgpu::TextureFormat,
+    ) -> Result<GlyphBrush<(), F, H>, String> {
+        validate(&self.settings)?;
+
+        Ok(self.build(device, render_format))
+    }
 }
 
 impl<F: Font + Sync, H: BuildHasher>
     GlyphBrushBuilder<wgpu::DepthStencilState, F, H>
 {
+    /// Sets the depth bias of the depth stencil state, replacing the `bias`
+    /// provided to
+    /// [`depth_stencil_state`](#method.depth_stencil_state).
+    ///
+    /// A bias can be used to draw text right on top of other coplanar
+    /// geometry (e.g. a label on a floor in world space) without z-fighting.
+    pub fn depth_bias(mut self, bias: wgpu::DepthBiasState) -> Self {
+        self.depth.bias = bias;
+        self
+    }
+
+    /// Sets whether glyphs write their depth, replacing the
+    /// `depth_write_enabled` provided to
+    /// [`depth_stencil_state`](#method.depth_stencil_state).
+    ///
+    /// Disabling it keeps testing glyphs against the depth of the scene, but
+    /// lets translucent text be drawn without occluding anything drawn after
+    /// it.
+    pub fn depth_write_enabled(mut self, depth_write_enabled: bool) -> Self {
+        self.depth.depth_write_enabled = depth_write_enabled;
+        self
+    }
+
+    /// Sets the comparison used to test the depth of glyphs, replacing the
+    /// `depth_compare` provided to
+    /// [`depth_stencil_state`](#method.depth_stencil_state).
+    ///
+    /// `wgpu::CompareFunction::Always` draws glyphs regardless of the depth
+    /// of the scene, while still writing their depth if enabled.
+    pub fn depth_compare(
+        mut self,
+        depth_compare: wgpu::CompareFunction,
+    ) -> Self {
+        self.depth.depth_compare = depth_compare;
+        self
+    }
+
     /// Builds a `GlyphBrush` using the given `wgpu::Device` that can render
     /// text for texture views with the given `render_format`.
     pub fn build(
@@ -131,10 +627,170 @@ impl<F: Font + Sync, H: BuildHasher>
     ) -> GlyphBrush<wgpu::DepthStencilState, F, H> {
         GlyphBrush::<wgpu::DepthStencilState, F, H>::new(
             device,
-            self.texture_filter_method,
             render_format,
             self.depth,
+            self.settings,
+            self.uniform_layout,
             self.inner,
         )
     }
+
+    /// Builds a `GlyphBrush` like [`build`](#method.build), but returns an
+    /// error instead of panicking if it cannot be created.
+    ///
+    /// The [`cache_format`](#method.cache_format) and the custom
+    /// [`shader`](#method.shader), if any, are validated before creating the
+    /// pipeline. The [`uniform_layout`](#method.uniform_layout) and the depth
+    /// stencil state cannot be checked ahead of time, so their errors are
+    /// still reported to the error handler of the `device` like with `build`.
+    pub fn try_build(
+        self,
+        device: &wgpu::Device,
+        render_format: wgpu::TextureFormat,
+    ) -> Result<GlyphBrush<wgpu::DepthStencilState, F, H>, String> {
+        validate(&self.settings)?;
+
+        Ok(self.build(device, render_format))
+    }
+}
+
+/// Checks the settings that `wgpu` would only reject once the `GlyphBrush` is
+/// used.
+fn validate(settings: &Settings) -> Result<(), String> {
+    let info = settings.cache_format.describe();
+
+    let samples_float = matches!(
+        info.sample_type,
+        wgpu::TextureSampleType::Float { filterable: true }
+    );
+
+    if info.block_dimensions != (1, 1)
+        || info.block_size != 1
+        || info.components != 1
+        || !samples_float
+    {
+        return Err(format!(
+            "The glyph cache format {:?} is not an 8-bit, single-channel, \
+            float-sampled format",
+            settings.cache_format
+        ));
+    }
+
+    match &settings.shader {
+        Some(shader) => validate_shader(shader),
+        None => Ok(()),
+    }
+}
+
+/// Parses and validates a custom shader, checking that it has the entry points
+/// it names.
+fn validate_shader(shader: &CustomShader) -> Result<(), String> {
+    let module =
+        naga::front::wgsl::parse_str(shader.source).map_err(|error| {
+            format!(
+                "The custom shader could not be parsed: {}",
+                error.emit_to_string(shader.source)
+            )
+        })?;
+
+    let _ = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|error| format!("The custom shader is invalid: {}", error))?;
+
+    let entry_points = [
+        (shader.vertex_entry_point, naga::ShaderStage::Vertex),
+        (shader.fragment_entry_point, naga::ShaderStage::Fragment),
+    ];
+
+    for (name, stage) in &entry_points {
+        let found = module.entry_points.iter().any(|entry_point| {
+            entry_point.name == *name && entry_point.stage == *stage
+        });
+
+        if !found {
+            return Err(format!(
+                "The custom shader has no {:?} entry point named {:?}",
+                stage, name
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(cache_format: wgpu::TextureFormat) -> Settings {
+        Settings {
+            cache_format,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn single_channel_float_cache_formats_are_valid() {
+        assert!(validate(&settings(wgpu::TextureFormat::R8Unorm)).is_ok());
+        assert!(validate(&settings(wgpu::TextureFormat::R8Snorm)).is_ok());
+    }
+
+    #[test]
+    fn other_cache_formats_are_invalid() {
+        for format in &[
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::TextureFormat::R16Float,
+            wgpu::TextureFormat::R8Uint,
+            wgpu::TextureFormat::Depth32Float,
+        ] {
+            assert!(validate(&settings(*format)).is_err(), "{:?}", format);
+        }
+    }
+
+    const SHADER: &str = include_str!("shader/glyph.wgsl");
+
+    #[test]
+    fn the_built_in_shader_is_valid_as_a_custom_shader() {
+        let shader = CustomShader::new(SHADER)
+            .with_vertex_entry_point("vs_main")
+            .with_fragment_entry_point("fs_main");
+
+        assert_eq!(validate_shader(&shader), Ok(()));
+    }
+
+    #[test]
+    fn shaders_missing_their_entry_points_are_invalid() {
+        let vertex = CustomShader::new(SHADER)
+            .with_vertex_entry_point("fs_main")
+            .with_fragment_entry_point("fs_main");
+
+        let fragment = CustomShader::new(SHADER)
+            .with_vertex_entry_point("vs_main")
+            .with_fragment_entry_point("missing");
+
+        assert!(validate_shader(&vertex).unwrap_err().contains("Vertex"));
+        assert!(validate_shader(&fragment).unwrap_err().contains("missing"));
+    }
+
+    #[test]
+    fn shaders_that_do_not_parse_are_invalid() {
+        let shader = CustomShader::new("fn main( {}");
+
+        assert!(validate_shader(&shader)
+            .unwrap_err()
+            .starts_with("The custom shader could not be parsed"));
+    }
+
+    #[test]
+    fn invalid_settings_are_reported_by_validate() {
+        let settings = Settings {
+            shader: Some(CustomShader::new("fn main( {}")),
+            ..Settings::default()
+        };
+
+        assert!(validate(&settings).is_err());
+    }
 }
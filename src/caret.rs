@@ -0,0 +1,187 @@
+use glyph_brush::ab_glyph::{point, Font, Rect, ScaleFont};
+use glyph_brush::{Section, SectionGlyph};
+use std::ops::Range;
+
+/// Returns the rectangle of a caret of the given `width` placed before the
+/// character at `index` of a laid out section, or after its last character
+/// when `index` is past the end.
+///
+/// Characters are counted across all the texts of the section.
+pub fn rect<F: Font, X>(
+    fonts: &[F],
+    section: &Section<'_, X>,
+    glyphs: &[SectionGlyph],
+    index: usize,
+    width: f32,
+) -> Rect {
+    let target = locate(section, index);
+
+    let metrics = |font_id: usize, scale| {
+        let font = fonts[font_id].as_scaled(scale);
+
+        (font.ascent(), font.descent(), font.line_gap())
+    };
+
+    let (x, baseline, (ascent, descent, _)) = match glyphs
+        .iter()
+        .find(|glyph| (glyph.section_index, glyph.byte_index) == target)
+    {
+        // The character has a glyph of its own
+        Some(glyph) => (
+            glyph.glyph.position.x,
+            glyph.glyph.position.y,
+            metrics(glyph.font_id.0, glyph.glyph.scale),
+        ),
+        None => {
+            let previous = glyphs
+                .iter()
+                .filter(|glyph| {
+                    (glyph.section_index, glyph.byte_index) < target
+                })
+                .max_by_key(|glyph| (glyph.section_index, glyph.byte_index));
+
+            match previous {
+                // The caret follows the previous glyph, on its line or on the
+                // lines after it
+                Some(glyph) => {
+                    let font =
+                        fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+                    let metrics = metrics(glyph.font_id.0, glyph.glyph.scale);
+                    let breaks = line_breaks(
+                        section,
+                        (glyph.section_index, glyph.byte_index),
+                        target,
+                    );
+
+                    let x = if breaks == 0 {
+                        glyph.glyph.position.x + font.h_advance(glyph.glyph.id)
+                    } else {
+                        section.screen_position.0
+                    };
+
+                    (
+                        x,
+                        glyph.glyph.position.y
+                            + breaks as f32 * line_height(metrics),
+                        metrics,
+                    )
+                }
+                // There are no glyphs before the caret, e.g. in an empty
+                // section or after leading line breaks
+                None => {
+                    let metrics = section
+                        .text
+                        .get(target.0)
+                        .map_or((0.0, 0.0, 0.0), |text| {
+                            metrics(text.font_id.0, text.scale)
+                        });
+                    let breaks = line_breaks(section, (0, 0), target);
+
+                    (
+                        section.screen_position.0,
+                        section.screen_position.1
+                            + metrics.0
+                            + breaks as f32 * line_height(metrics),
+                        metrics,
+                    )
+                }
+            }
+        }
+    };
+
+    Rect {
+        min: point(x, baseline - ascent),
+        max: point(x + width, baseline - descent),
+    }
+}
+
+/// Returns the rectangles covering the characters of a laid out section in
+/// the given `range`, one for every line they span.
+///
+/// Every rectangle spans the selected glyphs of its line, whitespace
+/// included, from the highest ascent to the lowest descent of their fonts.
+/// Lines without any glyph, like the ones produced by consecutive line
+/// breaks, have no rectangle.
+pub fn selection<F: Font, X>(
+    fonts: &[F],
+    section: &Section<'_, X>,
+    glyphs: &[SectionGlyph],
+    range: Range<usize>,
+) -> Vec<Rect> {
+    if range.start >= range.end {
+        return Vec::new();
+    }
+
+    let start = locate(section, range.start);
+    let end = locate(section, range.end);
+
+    let mut rects: Vec<(f32, Rect)> = Vec::new();
+
+    for glyph in glyphs.iter().filter(|glyph| {
+        let position = (glyph.section_index, glyph.byte_index);
+
+        start <= position && position < end
+    }) {
+        let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+        let baseline = glyph.glyph.position.y;
+        let left = glyph.glyph.position.x;
+        let rect = Rect {
+            min: point(left, baseline - font.ascent()),
+            max: point(
+                left + font.h_advance(glyph.glyph.id),
+                baseline - font.descent(),
+            ),
+        };
+
+        match rects.last_mut() {
+            Some((line, last)) if *line == baseline => {
+                last.min.x = last.min.x.min(rect.min.x);
+                last.min.y = last.min.y.min(rect.min.y);
+                last.max.x = last.max.x.max(rect.max.x);
+                last.max.y = last.max.y.max(rect.max.y);
+            }
+            _ => rects.push((baseline, rect)),
+        }
+    }
+
+    rects.into_iter().map(|(_, rect)| rect).collect()
+}
+
+fn line_height((ascent, descent, line_gap): (f32, f32, f32)) -> f32 {
+    ascent - descent + line_gap
+}
+
+/// Returns the text index and the byte index of the character at `index`,
+/// or the end of the section if there are not enough characters.
+fn locate<X>(section: &Section<'_, X>, mut index: usize) -> (usize, usize) {
+    for (text_index, text) in section.text.iter().enumerate() {
+        match text.text.char_indices().nth(index) {
+            Some((byte_index, _)) => return (text_index, byte_index),
+            None => index -= text.text.chars().count(),
+        }
+    }
+
+    match section.text.last() {
+        Some(text) => (section.text.len() - 1, text.text.len()),
+        None => (0, 0),
+    }
+}
+
+/// Counts the line breaks in the text of the section between two positions.
+fn line_breaks<X>(
+    section: &Section<'_, X>,
+    from: (usize, usize),
+    to: (usize, usize),
+) -> usize {
+    (from.0..=to.0.min(section.text.len().saturating_sub(1)))
+        .filter_map(|index| section.text.get(index))
+        .enumerate()
+        .map(|(offset, text)| {
+            let index = from.0 + offset;
+            let start = if index == from.0 { from.1 } else { 0 };
+            let end = if index == to.0 { to.1 } else { text.text.len() };
+
+            text.text[start..end].matches('\n').count()
+        })
+        .sum()
+}
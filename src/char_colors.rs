@@ -0,0 +1,57 @@
+use glyph_brush::{Section, Text};
+
+/// Splits the texts of a [`Section`] into runs of consecutive characters
+/// sharing the same color, taken from `colors` in the order of the characters
+/// of all the texts.
+///
+/// Characters past the end of `colors` keep the color of their text.
+pub fn apply<'a>(section: &'a Section<'_>, colors: &[[f32; 4]]) -> Section<'a> {
+    let mut colors = colors.iter();
+    let mut text = Vec::with_capacity(section.text.len());
+
+    for run in &section.text {
+        let mut start = 0;
+        let mut current = None;
+
+        for (index, _) in run.text.char_indices() {
+            let color = colors.next().copied().unwrap_or(run.extra.color);
+
+            match current {
+                Some(previous) if previous == color => {}
+                Some(previous) => {
+                    text.push(with_color(
+                        run,
+                        &run.text[start..index],
+                        previous,
+                    ));
+                    start = index;
+                    current = Some(color);
+                }
+                None => current = Some(color),
+            }
+        }
+
+        if let Some(color) = current {
+            text.push(with_color(run, &run.text[start..], color));
+        }
+    }
+
+    Section {
+        screen_position: section.screen_position,
+        bounds: section.bounds,
+        layout: section.layout,
+        text,
+    }
+}
+
+fn with_color<'a>(run: &Text<'_>, text: &'a str, color: [f32; 4]) -> Text<'a> {
+    Text {
+        text,
+        scale: run.scale,
+        font_id: run.font_id,
+        extra: glyph_brush::Extra {
+            color,
+            z: run.extra.z,
+        },
+    }
+}
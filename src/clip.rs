@@ -0,0 +1,41 @@
+/// A rounded rectangle used to clip the text drawn by a
+/// [`GlyphBrush`](struct.GlyphBrush.html), with antialiased edges.
+///
+/// It is positioned in physical pixels of the render target, from its
+/// top-left corner, regardless of the transform used to draw.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundedClip {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// The radius of the corners. It is limited to half of the smallest side,
+    /// which turns a square into a circle.
+    pub radius: f32,
+}
+
+impl RoundedClip {
+    /// Returns the clip as uploaded into the uniforms of the shader: its
+    /// center and half extents, followed by its corner radius and whether
+    /// clipping is enabled.
+    pub(crate) fn uniforms(clip: Option<RoundedClip>) -> [f32; 8] {
+        match clip {
+            Some(clip) => {
+                let half_width = clip.width.max(0.0) / 2.0;
+                let half_height = clip.height.max(0.0) / 2.0;
+
+                [
+                    clip.x + half_width,
+                    clip.y + half_height,
+                    half_width,
+                    half_height,
+                    clip.radius.max(0.0).min(half_width.min(half_height)),
+                    1.0,
+                    0.0,
+                    0.0,
+                ]
+            }
+            None => [0.0; 8],
+        }
+    }
+}
@@ -0,0 +1,103 @@
+/// The color space of the colors given to a
+/// [`GlyphBrush`](struct.GlyphBrush.html), i.e. the colors of sections and
+/// instances.
+///
+/// Colors are converted to the space of the render target when drawing:
+/// targets with an sRGB format expect linear colors and encode them on write,
+/// while any other target stores the colors as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// sRGB encoded colors, like the ones picked in most design tools and
+    /// written in CSS.
+    Srgb,
+    /// Linear colors.
+    Linear,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for ColorSpace {
+    fn default() -> Self {
+        ColorSpace::Srgb
+    }
+}
+
+impl ColorSpace {
+    /// Returns the vertex entry point of the shader converting colors from
+    /// this space to the space of a target with the given `render_format`.
+    pub(crate) fn vertex_entry_point(
+        self,
+        render_format: wgpu::TextureFormat,
+    ) -> &'static str {
+        match (self, render_format.describe().srgb) {
+            (ColorSpace::Srgb, true) => "vs_main_srgb_to_linear",
+            (ColorSpace::Linear, false) => "vs_main_linear_to_srgb",
+            _ => "vs_main",
+        }
+    }
+}
+
+/// Converts a color with 8-bit channels into the color of the API, keeping
+/// it in the same space, e.g. an sRGB color picked in a design tool for a
+/// brush using [`ColorSpace::Srgb`](enum.ColorSpace.html), the default.
+pub fn color_from_rgba8(r: u8, g: u8, b: u8, a: u8) -> [f32; 4] {
+    [r, g, b, a].map(|channel| f32::from(channel) / 255.0)
+}
+
+/// Converts a color written as `0xRRGGBBAA`, like `0xff8800ff` for an opaque
+/// orange, into the color of the API, keeping it in the same space. See
+/// [`color_from_rgba8`](fn.color_from_rgba8.html).
+pub fn color_from_hex(rgba: u32) -> [f32; 4] {
+    let [r, g, b, a] = rgba.to_be_bytes();
+
+    color_from_rgba8(r, g, b, a)
+}
+
+/// Converts an sRGB encoded color with 8-bit channels into a linear color,
+/// for a brush using [`ColorSpace::Linear`](enum.ColorSpace.html).
+///
+/// The alpha channel is not encoded, so it is only scaled.
+pub fn color_from_srgb8(r: u8, g: u8, b: u8, a: u8) -> [f32; 4] {
+    let decode = |channel: u8| {
+        let channel = f32::from(channel) / 255.0;
+
+        if channel <= 0.04045 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    [decode(r), decode(g), decode(b), f32::from(a) / 255.0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgba8_channels_are_normalized() {
+        assert_eq!(color_from_rgba8(255, 0, 51, 255), [1.0, 0.0, 0.2, 1.0]);
+    }
+
+    #[test]
+    fn hex_colors_are_read_as_rrggbbaa() {
+        assert_eq!(
+            color_from_hex(0xff8800ff),
+            color_from_rgba8(255, 136, 0, 255)
+        );
+    }
+
+    #[test]
+    fn srgb8_colors_are_decoded_to_linear() {
+        let [r, g, b, a] = color_from_srgb8(255, 188, 0, 128);
+
+        assert_eq!((r, b), (1.0, 0.0));
+        assert!((g - 0.5).abs() < 0.005);
+        assert_eq!(a, 128.0 / 255.0);
+    }
+
+    #[test]
+    fn srgb8_dark_channels_are_decoded_linearly() {
+        assert_eq!(color_from_srgb8(10, 0, 0, 0)[0], 10.0 / 255.0 / 12.92);
+    }
+}
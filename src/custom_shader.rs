@@ -0,0 +1,43 @@
+/// A WGSL shader replacing the built-in one of a
+/// [`GlyphBrush`](struct.GlyphBrush.html), set with
+/// [`GlyphBrushBuilder::shader`](struct.GlyphBrushBuilder.html#method.shader).
+///
+/// The shader receives the same instances and bind group as the built-in one,
+/// so it should declare the same vertex inputs and bindings, which can be
+/// found in `src/shader/glyph.wgsl`. Its entry points are used for every
+/// [`ColorSpace`](enum.ColorSpace.html) and whether or not
+/// [`premultiplied_output`](struct.GlyphBrushBuilder.html#method.premultiplied_output)
+/// is enabled, so the shader has to convert its colors itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomShader {
+    /// The WGSL source of the shader.
+    pub source: &'static str,
+    /// The name of the vertex entry point. Defaults to `"main"`.
+    pub vertex_entry_point: &'static str,
+    /// The name of the fragment entry point. Defaults to `"main"`.
+    pub fragment_entry_point: &'static str,
+}
+
+impl CustomShader {
+    /// Creates a [`CustomShader`] from its WGSL source, with both entry
+    /// points named `"main"`.
+    pub fn new(source: &'static str) -> CustomShader {
+        CustomShader {
+            source,
+            vertex_entry_point: "main",
+            fragment_entry_point: "main",
+        }
+    }
+
+    #[inline]
+    pub fn with_vertex_entry_point(mut self, name: &'static str) -> Self {
+        self.vertex_entry_point = name;
+        self
+    }
+
+    #[inline]
+    pub fn with_fragment_entry_point(mut self, name: &'static str) -> Self {
+        self.fragment_entry_point = name;
+        self
+    }
+}
@@ -0,0 +1,15 @@
+/// What a draw call of a [`GlyphBrush`](struct.GlyphBrush.html) changed in
+/// the glyph cache while processing the queue.
+///
+/// When nothing changed, command buffers recorded with the glyphs of a
+/// previous draw can be reused as they are, as long as the queued sections
+/// did not change either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawResult {
+    /// Whether the glyph cache had to grow to fit the queued glyphs.
+    pub cache_grew: bool,
+    /// The amount of glyphs uploaded to the glyph cache, including the ones
+    /// deferred by previous draws because of the
+    /// [`upload_budget`](struct.GlyphBrushBuilder.html#method.upload_budget).
+    pub glyphs_uploaded: usize,
+}
@@ -0,0 +1,185 @@
+use crate::Instance;
+
+use std::mem;
+
+const MAGIC: &[u8; 4] = b"WGID";
+const VERSION: u32 = 1;
+const INSTANCE_WORDS: usize = mem::size_of::<Instance>() / 4;
+
+/// Encodes the given instances and transform, in the format described by
+/// [`GlyphBrush::dump_frame`](struct.GlyphBrush.html#method.dump_frame).
+pub fn encode(instances: &[Instance], transform: [f32; 16]) -> Vec<u8> {
+    let mut bytes =
+        Vec::with_capacity(4 * (20 + instances.len() * INSTANCE_WORDS));
+
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(INSTANCE_WORDS as u32).to_le_bytes());
+
+    for value in transform {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    bytes.extend_from_slice(&(instances.len() as u32).to_le_bytes());
+
+    for word in bytemuck::cast_slice::<Instance, u32>(instances) {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+
+    bytes
+}
+
+/// Decodes the instances and transform of a dumped frame.
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+pub fn decode(bytes: &[u8]) -> Result<(Vec<Instance>, [f32; 16]), String> {
+    let mut words = bytes
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]));
+
+    if bytes.len() % 4 != 0 || bytes.get(..4) != Some(&MAGIC[..]) {
+        return Err(String::from("The dump is not a frame of wgpu_glyph"));
+    }
+
+    let _ = words.next();
+    let mut next = || {
+        words
+            .next()
+            .ok_or_else(|| String::from("The dump is truncated"))
+    };
+
+    let version = next()?;
+
+    if version != VERSION {
+        return Err(format!("The dump has an unknown version {}", version));
+    }
+
+    if next()? as usize != INSTANCE_WORDS {
+        return Err(String::from(
+            "The dump was written with a different instance layout",
+        ));
+    }
+
+    let mut transform = [0.0; 16];
+
+    for value in &mut transform {
+        *value = f32::from_bits(next()?);
+    }
+
+    let count = next()? as usize;
+    let length = count
+        .checked_mul(INSTANCE_WORDS)
+        .ok_or_else(|| String::from("The dump is truncated"))?;
+
+    let data = (0..length)
+        .map(|_| next())
+        .collect::<Result<Vec<u32>, String>>()?;
+
+    Ok((bytemuck::cast_slice(&data).to_vec(), transform))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::{point, Rect};
+
+    fn instance(x: f32) -> Instance {
+        Instance::new(
+            Rect {
+                min: point(x, 0.0),
+                max: point(x + 10.0, 20.0),
+            },
+            Rect {
+                min: point(0.0, 0.0),
+                max: point(0.25, 0.5),
+            },
+            0.5,
+            [1.0, 0.5, 0.25, 1.0],
+        )
+    }
+
+    fn transform() -> [f32; 16] {
+        let mut transform = [0.0; 16];
+
+        for (i, value) in transform.iter_mut().enumerate() {
+            *value = i as f32;
+        }
+
+        transform
+    }
+
+    fn word(bytes: &[u8], index: usize) -> u32 {
+        let word = &bytes[index * 4..index * 4 + 4];
+
+        u32::from_le_bytes([word[0], word[1], word[2], word[3]])
+    }
+
+    #[test]
+    fn round_trips() {
+        let instances = [instance(0.0), instance(10.0)];
+
+        let decoded = decode(&encode(&instances, transform())).unwrap();
+
+        assert_eq!(decoded, (instances.to_vec(), transform()));
+    }
+
+    #[test]
+    fn has_a_stable_layout() {
+        let bytes = encode(&[instance(3.0)], transform());
+
+        assert_eq!(&bytes[..4], b"WGID");
+        assert_eq!(word(&bytes, 1), 1);
+        assert_eq!(word(&bytes, 2) as usize, INSTANCE_WORDS);
+
+        for i in 0..16 {
+            assert_eq!(f32::from_bits(word(&bytes, 3 + i)), i as f32);
+        }
+
+        assert_eq!(word(&bytes, 19), 1);
+        assert_eq!(f32::from_bits(word(&bytes, 20)), 3.0);
+        assert_eq!(bytes.len(), 4 * (20 + INSTANCE_WORDS));
+    }
+
+    #[test]
+    fn rejects_truncated_dumps() {
+        let bytes = encode(&[instance(0.0)], transform());
+
+        assert_eq!(
+            decode(&bytes[..bytes.len() - 4]),
+            Err(String::from("The dump is truncated"))
+        );
+        assert_eq!(
+            decode(&bytes[..bytes.len() - 1]),
+            Err(String::from("The dump is not a frame of wgpu_glyph"))
+        );
+    }
+
+    #[test]
+    fn rejects_overflowing_counts() {
+        let mut bytes = encode(&[], transform());
+        let count = bytes.len() - 4;
+
+        bytes[count..].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let mut bytes = encode(&[instance(0.0)], transform());
+
+        bytes[4..8].copy_from_slice(&2u32.to_le_bytes());
+
+        assert_eq!(
+            decode(&bytes),
+            Err(String::from("The dump has an unknown version 2"))
+        );
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert_eq!(
+            decode(b"PNG\0"),
+            Err(String::from("The dump is not a frame of wgpu_glyph"))
+        );
+    }
+}
@@ -0,0 +1,56 @@
+/// An animated effect displacing every glyph drawn by a
+/// [`GlyphBrush`](struct.GlyphBrush.html), driven by the time set with
+/// [`set_time`](struct.GlyphBrush.html#method.set_time).
+///
+/// Glyphs are told apart by their index within their section, and displaced
+/// in the same pixel coordinates used to position sections. Backgrounds and
+/// decorations are not displaced.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    /// Moves glyphs vertically following a sine wave.
+    Wave {
+        /// The maximum displacement, in pixels.
+        amplitude: f32,
+        /// The phase difference between consecutive glyphs, in radians.
+        frequency: f32,
+        /// How fast the wave moves, in radians per unit of time.
+        speed: f32,
+    },
+    /// Moves glyphs randomly around their position.
+    Jitter {
+        /// The maximum displacement on each axis, in pixels.
+        amplitude: f32,
+        /// How many times per unit of time the glyphs move.
+        speed: f32,
+    },
+}
+
+impl Effect {
+    /// Returns the effect as uploaded into the uniforms of the shader: its
+    /// kind, the time and its parameters, followed by whether glyphs are faded
+    /// in and the progress of the fade.
+    pub(crate) fn uniforms(
+        effect: Option<Effect>,
+        time: f32,
+        fade_progress: Option<f32>,
+    ) -> [f32; 8] {
+        let mut uniforms = match effect {
+            Some(Effect::Wave {
+                amplitude,
+                frequency,
+                speed,
+            }) => [1.0, time, amplitude, frequency, speed, 0.0, 0.0, 0.0],
+            Some(Effect::Jitter { amplitude, speed }) => {
+                [2.0, time, amplitude, 0.0, speed, 0.0, 0.0, 0.0]
+            }
+            None => [0.0; 8],
+        };
+
+        if let Some(progress) = fade_progress {
+            uniforms[5] = 1.0;
+            uniforms[6] = progress;
+        }
+
+        uniforms
+    }
+}
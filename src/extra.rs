@@ -0,0 +1,417 @@
+use crate::builder::Settings;
+use crate::gradient::Span;
+use crate::style::Style;
+use crate::Instance;
+use glyph_brush::ab_glyph::Rect;
+use glyph_brush::{Extra, Section, SectionGlyph, Text};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// The extra data of every text queued into the inner `glyph_brush`.
+///
+/// It carries the styling applied to whole sections by the
+/// [`GlyphBrush`](struct.GlyphBrush.html), on top of the [`Extra`] of the
+/// text itself.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct GlyphExtra {
+    pub extra: Extra,
+    pub style: Style,
+    pub gradient: Option<Span>,
+    pub reveal: Option<Reveal>,
+    /// The identifier of the section of the text, telling consecutive
+    /// sections apart.
+    ///
+    /// It is hashed from the contents of the section, so that the section
+    /// hashes the same no matter what is queued or measured before it.
+    pub section: u64,
+}
+
+/// The limit of glyphs drawn for a section, identified by its queue order
+/// among the sections queued with a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Reveal {
+    pub section: usize,
+    pub count: usize,
+}
+
+/// A vertex produced by the inner `glyph_brush`.
+#[derive(Debug, Clone, Copy)]
+pub struct Vertex {
+    pub instance: Instance,
+    pub reveal: Option<Reveal>,
+    /// The identifier of the section of the glyph, telling sections apart.
+    pub section: u64,
+}
+
+impl Vertex {
+    /// Numbers the glyphs of every section, keeps the first glyphs of every
+    /// section with a [`Reveal`] limit, and removes the rest and any glyphs
+    /// that cover no area at all.
+    ///
+    /// Consecutive sections with the same contents are numbered as one.
+    pub fn into_instances(vertices: Vec<Vertex>) -> Vec<Instance> {
+        let mut current = None;
+        let mut revealed = 0;
+        let mut section = None;
+        let mut index = 0;
+
+        vertices
+            .into_iter()
+            .map(|mut vertex| {
+                if section != Some(vertex.section) {
+                    section = Some(vertex.section);
+                    index = 0;
+                }
+
+                vertex.instance = vertex.instance.with_index(index);
+                index += 1;
+
+                vertex
+            })
+            .filter(|vertex| {
+                if vertex.instance.is_empty() {
+                    return false;
+                }
+
+                match vertex.reveal {
+                    Some(reveal) => {
+                        if current != Some(reveal.section) {
+                            current = Some(reveal.section);
+                            revealed = 0;
+                        }
+
+                        revealed += 1;
+                        revealed <= reveal.count
+                    }
+                    None => true,
+                }
+            })
+            .map(|vertex| vertex.instance)
+            .collect()
+    }
+}
+
+impl From<Extra> for GlyphExtra {
+    fn from(extra: Extra) -> GlyphExtra {
+        GlyphExtra {
+            extra,
+            style: Style::default(),
+            gradient: None,
+            reveal: None,
+            section: 0,
+        }
+    }
+}
+
+/// Converts a [`Section`] into a section of the inner `glyph_brush`, with no
+/// additional styling, positioning it following the given [`Settings`].
+pub fn section<'a>(
+    section: &Section<'a>,
+    settings: &Settings,
+) -> Section<'a, GlyphExtra> {
+    let id = section_id(section);
+
+    Section {
+        screen_position: settings.screen_position(section.screen_position),
+        bounds: section.bounds,
+        layout: section.layout,
+        text: section
+            .text
+            .iter()
+            .map(|text| Text {
+                text: text.text,
+                scale: text.scale,
+                font_id: text.font_id,
+                extra: GlyphExtra {
+                    section: id,
+                    ..GlyphExtra::from(text.extra)
+                },
+            })
+            .collect(),
+    }
+}
+
+/// Returns a copy of the [`Section`] with all of its texts in the given color.
+pub fn with_color<'a>(section: &Section<'a>, color: [f32; 4]) -> Section<'a> {
+    let mut section = section.clone();
+
+    for text in &mut section.text {
+        text.extra.color = color;
+    }
+
+    section
+}
+
+/// Returns the identifier of a [`Section`], hashed from its contents.
+fn section_id(section: &Section<'_>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    section.screen_position.0.to_bits().hash(&mut hasher);
+    section.screen_position.1.to_bits().hash(&mut hasher);
+    section.bounds.0.to_bits().hash(&mut hasher);
+    section.bounds.1.to_bits().hash(&mut hasher);
+    section.layout.hash(&mut hasher);
+
+    for text in &section.text {
+        text.text.hash(&mut hasher);
+        text.scale.x.to_bits().hash(&mut hasher);
+        text.scale.y.to_bits().hash(&mut hasher);
+        text.font_id.hash(&mut hasher);
+        text.extra.hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+/// Returns the identifier of pre-positioned glyphs, hashed from their
+/// positions and bounds.
+pub fn pre_positioned_id(glyphs: &[SectionGlyph], bounds: Rect) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    for value in &[bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y] {
+        value.to_bits().hash(&mut hasher);
+    }
+
+    for glyph in glyphs {
+        glyph.font_id.hash(&mut hasher);
+        glyph.glyph.id.hash(&mut hasher);
+        glyph.glyph.position.x.to_bits().hash(&mut hasher);
+        glyph.glyph.position.y.to_bits().hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::{point, Font, FontRef};
+    use glyph_brush::{
+        GlyphChange, GlyphPositioner, Layout, SectionGeometry, ToSectionText,
+    };
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn vertex(section: u64, x: f32, width: f32) -> Vertex {
+        let bounds = Rect {
+            min: point(x, 0.0),
+            max: point(x + width, 20.0),
+        };
+
+        Vertex {
+            instance: Instance::new(bounds, bounds, 0.0, [1.0; 4]),
+            reveal: None,
+            section,
+        }
+    }
+
+    /// Returns the instances expected from the given vertices, numbered with
+    /// the given indices.
+    fn numbered(vertices: &[Vertex], indices: &[u32]) -> Vec<Instance> {
+        vertices
+            .iter()
+            .zip(indices)
+            .map(|(vertex, &index)| vertex.instance.with_index(index))
+            .collect()
+    }
+
+    #[test]
+    fn indices_rise_per_glyph_and_reset_per_section() {
+        let vertices = vec![
+            vertex(0, 0.0, 10.0),
+            vertex(0, 10.0, 10.0),
+            vertex(0, 20.0, 10.0),
+            vertex(1, 0.0, 10.0),
+            vertex(1, 10.0, 10.0),
+            vertex(2, 0.0, 10.0),
+        ];
+
+        assert_eq!(
+            Vertex::into_instances(vertices.clone()),
+            numbered(&vertices, &[0, 1, 2, 0, 1, 0])
+        );
+    }
+
+    #[test]
+    fn sections_with_equal_bounds_are_numbered_apart() {
+        // Both sections are laid out at the same place, so the glyphs of the
+        // second one repeat the bounds of the first one.
+        let vertices = vec![
+            vertex(0, 0.0, 10.0),
+            vertex(0, 10.0, 10.0),
+            vertex(1, 0.0, 10.0),
+            vertex(1, 10.0, 10.0),
+        ];
+
+        assert_eq!(
+            Vertex::into_instances(vertices.clone()),
+            numbered(&vertices, &[0, 1, 0, 1])
+        );
+    }
+
+    #[test]
+    fn empty_glyphs_are_removed_but_numbered() {
+        let vertices = vec![
+            vertex(0, 0.0, 10.0),
+            vertex(0, 10.0, 0.0),
+            vertex(0, 10.0, 10.0),
+        ];
+
+        assert_eq!(
+            Vertex::into_instances(vertices.clone()),
+            vec![
+                vertices[0].instance.with_index(0),
+                vertices[2].instance.with_index(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn reveal_limits_the_glyphs_of_its_section() {
+        let reveal = Some(Reveal {
+            section: 0,
+            count: 2,
+        });
+
+        let vertices: Vec<Vertex> = (0..4)
+            .map(|i| Vertex {
+                reveal,
+                ..vertex(0, i as f32 * 10.0, 10.0)
+            })
+            .chain(Some(vertex(1, 0.0, 10.0)))
+            .collect();
+
+        assert_eq!(
+            Vertex::into_instances(vertices.clone()),
+            vec![
+                vertices[0].instance.with_index(0),
+                vertices[1].instance.with_index(1),
+                vertices[4].instance.with_index(0),
+            ]
+        );
+    }
+
+    /// A layout counting how many times it lays out glyphs.
+    #[derive(Debug, Clone)]
+    struct Counting {
+        layout: Layout<glyph_brush::BuiltInLineBreaker>,
+        count: Rc<Cell<usize>>,
+    }
+
+    impl Hash for Counting {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.layout.hash(state);
+        }
+    }
+
+    impl GlyphPositioner for Counting {
+        fn calculate_glyphs<F, S>(
+            &self,
+            fonts: &[F],
+            geometry: &SectionGeometry,
+            sections: &[S],
+        ) -> Vec<SectionGlyph>
+        where
+            F: Font,
+            S: ToSectionText,
+        {
+            self.count.set(self.count.get() + 1);
+            self.layout.calculate_glyphs(fonts, geometry, sections)
+        }
+
+        fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+            self.layout.bounds_rect(geometry)
+        }
+
+        fn recalculate_glyphs<F, S, P>(
+            &self,
+            _previous: P,
+            _change: GlyphChange,
+            fonts: &[F],
+            geometry: &SectionGeometry,
+            sections: &[S],
+        ) -> Vec<SectionGlyph>
+        where
+            F: Font,
+            S: ToSectionText,
+            P: IntoIterator<Item = SectionGlyph>,
+        {
+            self.calculate_glyphs(fonts, geometry, sections)
+        }
+    }
+
+    fn text_section(text: &str, y: f32) -> Section<'_> {
+        Section {
+            screen_position: (0.0, y),
+            text: vec![Text::new(text).with_scale(20.0)],
+            ..Section::default()
+        }
+    }
+
+    #[test]
+    fn sections_are_identified_by_their_contents() {
+        let settings = Settings::default();
+        let a = text_section("a", 0.0);
+
+        let first = section(&a, &settings);
+        let _ = section(&text_section("b", 20.0), &settings);
+        let second = section(&a, &settings);
+
+        assert_eq!(first.text[0].extra, second.text[0].extra);
+        assert_ne!(
+            first.text[0].extra.section,
+            section(&text_section("b", 0.0), &settings).text[0]
+                .extra
+                .section
+        );
+    }
+
+    #[test]
+    fn keeping_a_section_cached_after_queueing_another_reuses_its_layout() {
+        let font = FontRef::try_from_slice(include_bytes!(
+            "../examples/Inconsolata-Regular.ttf"
+        ))
+        .unwrap();
+
+        let mut glyph_brush: glyph_brush::GlyphBrush<(), GlyphExtra, _> =
+            glyph_brush::GlyphBrushBuilder::using_font(font).build();
+
+        let settings = Settings::default();
+        let count = Rc::new(Cell::new(0));
+        let layout = Counting {
+            layout: Layout::default(),
+            count: count.clone(),
+        };
+
+        let a = text_section("a", 0.0);
+        let b = text_section("b", 20.0);
+
+        glyph_brush.queue_custom_layout(section(&a, &settings), &layout);
+        let _ = glyph_brush.process_queued(|_, _| {}, |_| ()).unwrap();
+
+        glyph_brush.queue_custom_layout(section(&b, &settings), &layout);
+        glyph_brush.keep_cached_custom_layout(section(&a, &settings), &layout);
+        let _ = glyph_brush.process_queued(|_, _| {}, |_| ()).unwrap();
+
+        glyph_brush.queue_custom_layout(section(&a, &settings), &layout);
+        let _ = glyph_brush.process_queued(|_, _| {}, |_| ()).unwrap();
+
+        // Only the first draws of both sections lay them out
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn sections_with_the_default_color_take_it_over_their_own() {
+        let section = Section::default()
+            .add_text(Text::new("a"))
+            .add_text(Text::new("b").with_color([0.0, 1.0, 0.0, 1.0]));
+        let red = [1.0, 0.0, 0.0, 1.0];
+
+        let colored =
+            self::section(&with_color(&section, red), &Settings::default());
+
+        for text in &colored.text {
+            assert_eq!(text.extra.extra.color, red);
+        }
+    }
+}
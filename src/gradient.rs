@@ -0,0 +1,104 @@
+use glyph_brush::ab_glyph::Rect;
+use std::hash::{Hash, Hasher};
+
+/// A two-stop linear color gradient applied across a whole section.
+///
+/// The gradient spans the pixel bounds of the glyphs of the section, and it
+/// replaces the colors of its texts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gradient {
+    /// Goes from the `left` color to the `right` color.
+    Horizontal { left: [f32; 4], right: [f32; 4] },
+    /// Goes from the `top` color to the `bottom` color.
+    ///
+    /// The top of a section is where its pixel coordinates are the smallest.
+    Vertical { top: [f32; 4], bottom: [f32; 4] },
+}
+
+/// A [`Gradient`] resolved against the pixel bounds it spans.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    gradient: Gradient,
+    start: f32,
+    end: f32,
+}
+
+impl Span {
+    pub fn new(gradient: Gradient, bounds: Rect) -> Span {
+        let (start, end) = match gradient {
+            Gradient::Horizontal { .. } => (bounds.min.x, bounds.max.x),
+            Gradient::Vertical { .. } => (bounds.min.y, bounds.max.y),
+        };
+
+        Span {
+            gradient,
+            start,
+            end,
+        }
+    }
+
+    /// Returns the colors at the smallest and largest pixel coordinates of the
+    /// given rectangle, along with the axis they change over.
+    pub fn colors(&self, rect: Rect) -> ([f32; 4], [f32; 4], [f32; 2]) {
+        let (from, to, min, max, axis) = match self.gradient {
+            Gradient::Horizontal { left, right } => {
+                (left, right, rect.min.x, rect.max.x, [1.0, 0.0])
+            }
+            Gradient::Vertical { top, bottom } => {
+                (top, bottom, rect.min.y, rect.max.y, [0.0, 1.0])
+            }
+        };
+
+        (
+            self.color_at(from, to, min),
+            self.color_at(from, to, max),
+            axis,
+        )
+    }
+
+    fn color_at(
+        &self,
+        from: [f32; 4],
+        to: [f32; 4],
+        position: f32,
+    ) -> [f32; 4] {
+        let length = self.end - self.start;
+
+        let t = if length > 0.0 {
+            ((position - self.start) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let mut color = [0.0; 4];
+
+        for (i, component) in color.iter_mut().enumerate() {
+            *component = from[i] + (to[i] - from[i]) * t;
+        }
+
+        color
+    }
+}
+
+impl Hash for Gradient {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (kind, from, to) = match *self {
+            Gradient::Horizontal { left, right } => (0u8, left, right),
+            Gradient::Vertical { top, bottom } => (1u8, top, bottom),
+        };
+
+        kind.hash(state);
+
+        for value in from.iter().chain(&to) {
+            value.to_bits().hash(state);
+        }
+    }
+}
+
+impl Hash for Span {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.gradient.hash(state);
+        self.start.to_bits().hash(state);
+        self.end.to_bits().hash(state);
+    }
+}
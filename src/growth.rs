@@ -0,0 +1,251 @@
+use glyph_brush::ab_glyph::{Font, PxScale};
+
+/// The policy used to choose the next size of the glyph cache of a
+/// [`GlyphBrush`](struct.GlyphBrush.html) when the queued glyphs do not fit in
+/// it.
+///
+/// If the new size is still too small, the cache grows again following the
+/// same policy. Whatever the policy, each side of the cache is limited to the
+/// `max_texture_dimension_2d` of the device, and drawing fails with an error
+/// once the cache cannot grow anymore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrowthPolicy {
+    /// Grows to the size suggested by `glyph_brush`.
+    Suggested,
+    /// Doubles the smallest side of the cache, or its width if both sides are
+    /// equal.
+    DoubleSmallest,
+    /// Grows each side to the next power of two of the size suggested by
+    /// `glyph_brush`.
+    NextPowerOfTwo,
+    /// Adds a fixed amount of pixels, at least one, to each side of the cache.
+    Fixed(u32),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for GrowthPolicy {
+    fn default() -> Self {
+        GrowthPolicy::Suggested
+    }
+}
+
+impl GrowthPolicy {
+    /// Returns the next size of a cache of the `current` size, given the size
+    /// `suggested` by `glyph_brush`, with each side limited to
+    /// `max_dimension`.
+    ///
+    /// Returns `None` if the cache cannot grow anymore.
+    pub(crate) fn next_size(
+        self,
+        current: (u32, u32),
+        suggested: (u32, u32),
+        max_dimension: u32,
+    ) -> Option<(u32, u32)> {
+        let (width, height) = match self {
+            GrowthPolicy::Suggested => suggested,
+            GrowthPolicy::DoubleSmallest => {
+                if current.0 <= current.1 {
+                    (current.0.saturating_mul(2), current.1)
+                } else {
+                    (current.0, current.1.saturating_mul(2))
+                }
+            }
+            GrowthPolicy::NextPowerOfTwo => (
+                suggested.0.next_power_of_two(),
+                suggested.1.next_power_of_two(),
+            ),
+            GrowthPolicy::Fixed(amount) => (
+                current.0.saturating_add(amount.max(1)),
+                current.1.saturating_add(amount.max(1)),
+            ),
+        };
+
+        let next = (
+            width.max(current.0).min(max_dimension),
+            height.max(current.1).min(max_dimension),
+        );
+
+        if next.0 <= current.0 && next.1 <= current.1 {
+            None
+        } else {
+            Some(next)
+        }
+    }
+}
+
+/// Estimates the size of a glyph cache able to hold the given characters of
+/// every font at the given `scale`, e.g. to choose the
+/// [`initial_cache_size`](struct.GlyphBrushBuilder.html#method.initial_cache_size)
+/// of a [`GlyphBrush`](struct.GlyphBrush.html) and avoid growing it right
+/// away.
+///
+/// Glyphs are packed in rows, tallest first, like the cache does, with some
+/// extra padding. Both sides of the size are powers of two. The estimate
+/// counts every glyph once, so drawing the same glyph at many different
+/// subpixel positions may still need a bigger cache.
+pub fn suggested_cache_size<F: Font>(
+    fonts: &[F],
+    chars: impl IntoIterator<Item = char>,
+    scale: impl Into<PxScale>,
+) -> (u32, u32) {
+    let scale = scale.into();
+
+    let mut chars: Vec<char> = chars.into_iter().collect();
+    chars.sort_unstable();
+    chars.dedup();
+
+    let mut sizes: Vec<(u32, u32)> = fonts
+        .iter()
+        .flat_map(|font| {
+            chars.iter().filter_map(move |&c| {
+                let id = font.glyph_id(c);
+
+                if id.0 == 0 {
+                    return None;
+                }
+
+                let bounds =
+                    font.outline_glyph(id.with_scale(scale))?.px_bounds();
+
+                // A pixel of padding on each side, and one more for the
+                // subpixel offset of the glyph
+                Some((bounds.width() as u32 + 3, bounds.height() as u32 + 3))
+            })
+        })
+        .collect();
+
+    sizes.sort_unstable_by_key(|&(_, height)| std::cmp::Reverse(height));
+
+    let area: u32 = sizes.iter().map(|(width, height)| width * height).sum();
+    let widest = sizes.iter().map(|(width, _)| *width).max().unwrap_or(1);
+
+    let mut width = ((area as f32).sqrt().ceil() as u32)
+        .max(widest)
+        .next_power_of_two();
+
+    loop {
+        let height = packed_height(&sizes, width);
+
+        if height <= width {
+            return (width, height.max(1).next_power_of_two());
+        }
+
+        width *= 2;
+    }
+}
+
+/// Returns the height needed to pack glyphs of the given sizes in rows of the
+/// given width.
+fn packed_height(sizes: &[(u32, u32)], width: u32) -> u32 {
+    let (mut x, mut y, mut row) = (0, 0, 0);
+
+    for &(glyph_width, glyph_height) in sizes {
+        if x + glyph_width > width {
+            x = 0;
+            y += row;
+            row = 0;
+        }
+
+        x += glyph_width;
+        row = row.max(glyph_height);
+    }
+
+    y + row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::FontRef;
+    use glyph_brush::{GlyphBrushBuilder, Section, Text};
+
+    #[test]
+    fn suggested_grows_to_the_suggestion() {
+        assert_eq!(
+            GrowthPolicy::Suggested.next_size((256, 256), (512, 256), 4096),
+            Some((512, 256))
+        );
+    }
+
+    #[test]
+    fn double_smallest_doubles_the_smallest_side() {
+        let policy = GrowthPolicy::DoubleSmallest;
+
+        assert_eq!(
+            policy.next_size((256, 256), (300, 300), 4096),
+            Some((512, 256))
+        );
+        assert_eq!(
+            policy.next_size((512, 256), (600, 600), 4096),
+            Some((512, 512))
+        );
+    }
+
+    #[test]
+    fn next_power_of_two_rounds_the_suggestion_up() {
+        assert_eq!(
+            GrowthPolicy::NextPowerOfTwo.next_size(
+                (256, 256),
+                (300, 257),
+                4096
+            ),
+            Some((512, 512))
+        );
+    }
+
+    #[test]
+    fn fixed_adds_at_least_one_pixel() {
+        assert_eq!(
+            GrowthPolicy::Fixed(0).next_size((256, 256), (300, 300), 4096),
+            Some((257, 257))
+        );
+    }
+
+    #[test]
+    fn sizes_are_limited_to_the_max_dimension() {
+        assert_eq!(
+            GrowthPolicy::Suggested.next_size((1024, 512), (8192, 8192), 2048),
+            Some((2048, 2048))
+        );
+        assert_eq!(
+            GrowthPolicy::Suggested.next_size((2048, 2048), (4096, 4096), 2048),
+            None
+        );
+    }
+
+    #[test]
+    fn caches_never_shrink() {
+        assert_eq!(
+            GrowthPolicy::Suggested.next_size((512, 512), (256, 1024), 4096),
+            Some((512, 1024))
+        );
+    }
+
+    #[test]
+    fn suggested_cache_size_fits_the_glyphs() {
+        let font = FontRef::try_from_slice(include_bytes!(
+            "../examples/Inconsolata-Regular.ttf"
+        ))
+        .unwrap();
+
+        let chars: String = (' '..='~').collect();
+        let size = suggested_cache_size(
+            std::slice::from_ref(&font),
+            chars.chars(),
+            32.0,
+        );
+
+        let mut glyph_brush: glyph_brush::GlyphBrush<(), _, _> =
+            GlyphBrushBuilder::using_font(font)
+                .initial_cache_size(size)
+                .build();
+
+        glyph_brush.queue(
+            Section::default().add_text(Text::new(&chars).with_scale(32.0)),
+        );
+
+        let result = glyph_brush.process_queued(|_, _| {}, |_| ());
+
+        assert!(result.is_ok(), "{:?} is too small", size);
+    }
+}
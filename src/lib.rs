@@ -2,14 +2,71 @@
 //!
 //! [`wgpu`]: https://github.com/gfx-rs/wgpu
 //! [`glyph_brush`]: https://github.com/alexheretic/glyph-brush/tree/master/glyph-brush
+//!
+//! # Coordinate spaces
+//! Sections, their bounds and their scale are laid out in physical pixels,
+//! and the transform used to draw maps those pixels to the render target,
+//! usually with an [`orthographic_projection`] of its physical size. On HiDPI
+//! displays, convert logical coordinates first with [`logical_to_physical`].
 #![deny(unused_results)]
+mod align;
 mod builder;
+mod caret;
+mod char_colors;
+mod clip;
+mod color;
+mod custom_shader;
+mod draw;
+mod dump;
+mod effect;
+mod extra;
+mod gradient;
+mod growth;
+mod lines;
+mod memory;
+mod metrics;
+mod origin;
+mod path;
 mod pipeline;
 mod region;
+mod retained;
+mod scroll;
+mod sprite;
+mod style;
+mod tabs;
+mod tracking;
+mod viewport;
+
+#[cfg(feature = "test_support")]
+pub mod test_support;
 
+pub use align::Alignment;
+pub use clip::RoundedClip;
+pub use color::{
+    color_from_hex, color_from_rgba8, color_from_srgb8, ColorSpace,
+};
+pub use custom_shader::CustomShader;
+pub use draw::DrawResult;
+pub use effect::Effect;
+pub use gradient::Gradient;
+pub use growth::{suggested_cache_size, GrowthPolicy};
+pub use lines::LineMetrics;
+pub use memory::MemoryReport;
+pub use metrics::VMetrics;
+pub use origin::Origin;
 pub use region::Region;
+pub use retained::SectionId;
+pub use scroll::{line_position, visible_line_range};
+pub use sprite::{SpriteFont, SpriteGlyph};
+pub use style::Style;
+pub use viewport::Viewport;
+
+use extra::{GlyphExtra, Reveal, Vertex};
+use pipeline::Pipeline;
+use retained::Retained;
+use tracking::Tracking;
 
-use pipeline::{Instance, Pipeline};
+use builder::Settings;
 
 pub use builder::GlyphBrushBuilder;
 pub use glyph_brush::ab_glyph;
@@ -19,21 +76,48 @@ pub use glyph_brush::{
     Section, SectionGeometry, SectionGlyph, SectionGlyphIter, SectionText,
     Text, VerticalAlign,
 };
+pub use pipeline::{uniform_layout_entries, Instance, Topology};
 
-use ab_glyph::{Font, Rect};
+use ab_glyph::{point, Font, PxScale, Rect, ScaleFont};
 use core::hash::BuildHasher;
 use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::path::Path;
 
 use glyph_brush::{BrushAction, BrushError, DefaultSectionHasher};
 use log::{log_enabled, warn};
 
+/// The sections kept cached during a batch, by their hash, with the layout
+/// they were queued with if it is not their own.
+type Batch = HashMap<u64, (OwnedSection<GlyphExtra>, Option<Tracking>)>;
+
+/// A callback invoked with the new dimensions of the glyph cache.
+type CacheResizeCallback = Box<dyn FnMut(u32, u32) + Send + Sync>;
+
 /// Object allowing glyph drawing, containing cache state. Manages glyph positioning cacheing,
 /// glyph draw caching & efficient GPU texture cache updating and re-sizing on demand.
 ///
 /// Build using a [`GlyphBrushBuilder`](struct.GlyphBrushBuilder.html).
 pub struct GlyphBrush<Depth, F = ab_glyph::FontArc, H = DefaultSectionHasher> {
     pipeline: Pipeline<Depth>,
-    glyph_brush: glyph_brush::GlyphBrush<Instance, Extra, F, H>,
+    glyph_brush: glyph_brush::GlyphBrush<Vertex, GlyphExtra, F, H>,
+    initial_cache_size: (u32, u32),
+    retained: Retained,
+    backgrounds: Vec<Instance>,
+    rects: Vec<Instance>,
+    uploaded: Vec<Instance>,
+    uploaded_rects: Range<usize>,
+    deferred: bool,
+    processed_generation: u64,
+    last_instances: Vec<Instance>,
+    decorations: Vec<Instance>,
+    revealed_sections: usize,
+    batch: Option<Batch>,
+    on_cache_resize: Option<CacheResizeCallback>,
+    settings: Settings,
 }
 
 impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
@@ -47,7 +131,207 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
     where
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush.queue(section)
+        let batching = self.batch.is_some();
+
+        let kept =
+            self.with_glyph_section(&section.into(), |glyph_brush, section| {
+                let kept = batching.then(|| section.to_owned());
+                glyph_brush.queue(section);
+
+                kept
+            });
+
+        self.keep_in_batch(kept, None);
+    }
+
+    /// Queues a section/layout to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), coloring
+    /// each of its characters with the color at the same position in
+    /// `colors`, e.g. to highlight syntax.
+    ///
+    /// The colors follow the characters of all the texts of the section, in
+    /// order, including whitespace and line breaks. Characters past the end
+    /// of `colors` keep the color of their text. Consecutive characters of
+    /// the same color are queued as a single text.
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    #[inline]
+    pub fn queue_with_colors<'a, S>(&mut self, section: S, colors: &[[f32; 4]])
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+
+        self.queue(char_colors::apply(&section, colors))
+    }
+
+    /// Queues a section/layout to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), drawing all
+    /// of its texts with the default color of the brush instead of their own,
+    /// e.g. for sections that leave the color to a theme.
+    ///
+    /// See [`GlyphBrushBuilder::default_color`](struct.GlyphBrushBuilder.html#method.default_color).
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    #[inline]
+    pub fn queue_with_default_color<'a, S>(&mut self, section: S)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let section = section.into();
+
+        self.queue(extra::with_color(&section, self.settings.default_color))
+    }
+
+    /// Queues a section/layout to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), applying a
+    /// color [`Gradient`](enum.Gradient.html) across all of its glyphs.
+    ///
+    /// The gradient replaces the colors of the texts of the section.
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    #[inline]
+    pub fn queue_with_gradient<'a, S>(&mut self, section: S, gradient: Gradient)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        self.queue_styled(section, Style::default().with_gradient(gradient))
+    }
+
+    /// Queues a section/layout to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), laid out
+    /// within the given rectangle with the given horizontal
+    /// [`Alignment`](enum.Alignment.html).
+    ///
+    /// The position, the bounds and the horizontal alignment of the section
+    /// are replaced so its lines start, center or end within the rectangle,
+    /// which is also the one glyphs are cropped to. Its vertical alignment is
+    /// kept, relative to the top, middle or bottom of the rectangle.
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    pub fn queue_aligned<'a, S>(
+        &mut self,
+        section: S,
+        rect: Rect,
+        alignment: Alignment,
+    ) where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let mut section = section.into().into_owned();
+
+        alignment.apply(&mut section, rect, self.settings.origin);
+
+        self.queue_styled(
+            section,
+            Style::default().with_justify(alignment == Alignment::Justified),
+        )
+    }
+
+    /// Queues a section/layout to be drawn by the next call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), applying
+    /// the given [`Style`](struct.Style.html) to the whole section.
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    pub fn queue_styled<'a, S>(&mut self, section: S, mut style: Style)
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let crop_to_bounds = self.settings.crop_to_bounds;
+        let origin = self.settings.origin;
+
+        style.crop_bounds = style.crop_bounds.map(|rect| origin.rect(rect));
+        style.scale_pivot =
+            style.scale_pivot.map(|pivot| origin.position(pivot));
+
+        let reveal = style.reveal.map(|count| {
+            let section = self.revealed_sections;
+            self.revealed_sections += 1;
+
+            Reveal { section, count }
+        });
+
+        let batching = self.batch.is_some();
+
+        let (background, decorations, kept) = self.with_glyph_section(
+            &section.into(),
+            |glyph_brush, mut section| {
+                let layout = Tracking::new(
+                    section.layout,
+                    style.tracking,
+                    style.advance_scale.unwrap_or(1.0),
+                    style.justify,
+                    style.tabular_figures,
+                    style.max_lines,
+                    style.ellipsis,
+                );
+
+                let bounds = if style.gradient.is_some()
+                    || style.background.is_some()
+                    || style.is_transformed()
+                {
+                    glyph_brush.glyph_bounds_custom_layout(&section, &layout)
+                } else {
+                    None
+                };
+
+                let mut style = style;
+
+                if let (true, None, Some(bounds)) =
+                    (style.is_transformed(), style.scale_pivot, bounds)
+                {
+                    style.scale_pivot = Some((
+                        (bounds.min.x + bounds.max.x) / 2.0,
+                        (bounds.min.y + bounds.max.y) / 2.0,
+                    ));
+                }
+
+                let span =
+                    style.gradient.zip(bounds).map(|(gradient, bounds)| {
+                        gradient::Span::new(gradient, style.scale_rect(bounds))
+                    });
+
+                for text in &mut section.text {
+                    text.extra.style = style;
+                    text.extra.gradient = span;
+                    text.extra.reveal = reveal;
+
+                    if let Some(z) = style.z {
+                        text.extra.extra.z = z;
+                    }
+                }
+
+                let background = bounds
+                    .and_then(|bounds| style::background(&section, bounds));
+
+                let decorations = if style.underline || style.strikethrough {
+                    let glyphs: Vec<_> = glyph_brush
+                        .glyphs_custom_layout(&section, &layout)
+                        .cloned()
+                        .collect();
+
+                    style::decorations(
+                        &section,
+                        &glyphs,
+                        glyph_brush.fonts(),
+                        crop_to_bounds,
+                    )
+                } else {
+                    Vec::new()
+                };
+
+                let kept = batching.then(|| (section.to_owned(), layout));
+                glyph_brush.queue_custom_layout(section, &layout);
+
+                (background, decorations, kept)
+            },
+        );
+
+        self.backgrounds.extend(background);
+        self.decorations.extend(decorations);
+
+        if let Some((section, layout)) = kept {
+            self.keep_in_batch(Some(section), Some(layout));
+        }
     }
 
     /// Queues a section/layout to be drawn by the next call of
@@ -68,7 +352,9 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
         G: GlyphPositioner,
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush.queue_custom_layout(section, custom_layout)
+        self.with_glyph_section(&section.into(), |glyph_brush, section| {
+            glyph_brush.queue_custom_layout(section, custom_layout)
+        })
     }
 
     /// Queues pre-positioned glyphs to be processed by the next call of
@@ -81,9 +367,33 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
         extra: Vec<Extra>,
         bounds: Rect,
     ) {
+        let id = extra::pre_positioned_id(&glyphs, bounds);
+
+        let extra = extra
+            .into_iter()
+            .map(|extra| GlyphExtra {
+                section: id,
+                ..GlyphExtra::from(extra)
+            })
+            .collect();
+
         self.glyph_brush.queue_pre_positioned(glyphs, extra, bounds)
     }
 
+    /// Queues a rectangle filled with a solid `color` to be drawn by the next
+    /// call of [`draw_queued`](struct.GlyphBrush.html#method.draw_queued),
+    /// e.g. a divider, a caret or a selection box.
+    ///
+    /// Rectangles use the same pipeline as glyphs, so they are drawn in the
+    /// same render pass, behind the glyphs and in front of the backgrounds of
+    /// the sections queued in the same draw. Like sections, they have to be
+    /// queued again for every draw.
+    pub fn queue_rect(&mut self, bounds: Rect, z: f32, color: [f32; 4]) {
+        let bounds = self.settings.origin.rect(bounds);
+
+        self.rects.push(Instance::solid(bounds, z, color));
+    }
+
     /// Retains the section in the cache as if it had been used in the last
     /// draw-frame.
     ///
@@ -98,8 +408,9 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
         S: Into<Cow<'a, Section<'a>>>,
         G: GlyphPositioner,
     {
-        self.glyph_brush
-            .keep_cached_custom_layout(section, custom_layout)
+        self.with_glyph_section(&section.into(), |glyph_brush, section| {
+            glyph_brush.keep_cached_custom_layout(section, custom_layout)
+        })
     }
 
     /// Retains the section in the cache as if it had been used in the last
@@ -112,7 +423,71 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
     where
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush.keep_cached(section)
+        self.with_glyph_section(&section.into(), |glyph_brush, section| {
+            glyph_brush.keep_cached(section)
+        })
+    }
+
+    /// Starts a batch of draws during which the layout of every section
+    /// queued is kept cached, even in the draws where it is not queued again,
+    /// until [`end_batch`](#method.end_batch) is called.
+    ///
+    /// This avoids laying out the same sections over and over during rapid
+    /// transitions between screens, at the cost of the memory of the cached
+    /// layouts. The glyph cache still grows if the glyphs drawn do not fit.
+    /// Sections queued with
+    /// [`queue_custom_layout`](#method.queue_custom_layout) are not kept.
+    pub fn begin_batch(&mut self) {
+        let _ = self.batch.get_or_insert_with(HashMap::new);
+    }
+
+    /// Ends the batch started with [`begin_batch`](#method.begin_batch).
+    ///
+    /// The next draw trims the cache as usual, keeping only the sections
+    /// queued for it.
+    pub fn end_batch(&mut self) {
+        self.batch = None;
+    }
+
+    fn keep_in_batch(
+        &mut self,
+        section: Option<OwnedSection<GlyphExtra>>,
+        layout: Option<Tracking>,
+    ) {
+        if let (Some(batch), Some(section)) = (&mut self.batch, section) {
+            let mut hasher = DefaultHasher::new();
+            section.to_borrowed().hash(&mut hasher);
+
+            let _ = batch.entry(hasher.finish()).or_insert((section, layout));
+        }
+    }
+
+    /// Retains a section to be drawn by every subsequent call of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued), until it is
+    /// removed with
+    /// [`remove_retained`](struct.GlyphBrush.html#method.remove_retained).
+    ///
+    /// Retained sections are queued right before drawing, after any sections
+    /// queued manually. Their position is fixed in pixels, but the transform
+    /// provided when drawing (e.g. with
+    /// [`draw_queued_with_transform`](struct.GlyphBrush.html#method.draw_queued_with_transform))
+    /// still applies to them.
+    ///
+    /// Returns a new [`SectionId`](struct.SectionId.html) to reference the
+    /// section.
+    pub fn retain(&mut self, section: OwnedSection) -> SectionId {
+        self.retained.insert(section)
+    }
+
+    /// Returns a mutable reference to a retained section, if it exists.
+    pub fn retained_mut(&mut self, id: SectionId) -> Option<&mut OwnedSection> {
+        self.retained.get_mut(id)
+    }
+
+    /// Stops drawing the retained section with the given
+    /// [`SectionId`](struct.SectionId.html) and returns it, if it exists.
+    pub fn remove_retained(&mut self, id: SectionId) -> Option<OwnedSection> {
+        self.retained.remove(id)
     }
 
     /// Returns the available fonts.
@@ -129,167 +504,1563 @@ impl<Depth, F: Font, H: BuildHasher> GlyphBrush<Depth, F, H> {
     pub fn add_font(&mut self, font: F) -> FontId {
         self.glyph_brush.add_font(font)
     }
+
+    /// Changes the color of the sections queued from now on with
+    /// [`queue_with_default_color`](#method.queue_with_default_color). See
+    /// [`GlyphBrushBuilder::default_color`](struct.GlyphBrushBuilder.html#method.default_color).
+    pub fn set_default_color(&mut self, color: [f32; 4]) {
+        self.settings.default_color = color;
+    }
+
+    /// Returns the vertical metrics of the font with the given `FontId` at
+    /// the given `scale`, or `None` if the brush has no such font.
+    ///
+    /// This allows positioning other elements relative to text, or computing
+    /// the height of rows, without laying out any section.
+    pub fn v_metrics(
+        &self,
+        font_id: FontId,
+        scale: impl Into<PxScale>,
+    ) -> Option<VMetrics> {
+        let font = self.glyph_brush.fonts().get(font_id.0)?.as_scaled(scale);
+
+        Some(VMetrics {
+            ascent: font.ascent(),
+            descent: font.descent(),
+            line_gap: font.line_gap(),
+        })
+    }
+
+    /// Returns the vertical metrics of every line of a section, from top to
+    /// bottom, as laid out when drawing it.
+    ///
+    /// Lines without any glyph, like the ones produced by consecutive line
+    /// breaks, are not included.
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    pub fn lines<'a, S>(&mut self, section: S) -> Vec<LineMetrics>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        self.with_glyph_section(&section.into(), |glyph_brush, section| {
+            let glyphs: Vec<_> = glyph_brush.glyphs(section).cloned().collect();
+
+            lines::lines(glyph_brush.fonts(), glyphs.iter())
+        })
+    }
+
+    /// Returns the amount of glyph instances the given sections produce once
+    /// drawn, laying them out without touching the GPU.
+    ///
+    /// Only glyphs with an outline are counted, so whitespace is not. Glyphs
+    /// cropped out of their bounds, backgrounds, decorations and rectangles
+    /// are not taken into account either, so the actual amount of instances
+    /// may differ slightly.
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    pub fn instance_count<'a, S>(
+        &mut self,
+        sections: impl IntoIterator<Item = S>,
+    ) -> usize
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        sections
+            .into_iter()
+            .map(|section| {
+                self.with_glyph_section(
+                    &section.into(),
+                    |glyph_brush, section| {
+                        let glyphs: Vec<_> =
+                            glyph_brush.glyphs(section).cloned().collect();
+                        let fonts = glyph_brush.fonts();
+
+                        glyphs
+                            .into_iter()
+                            .filter(|glyph| {
+                                fonts[glyph.font_id.0]
+                                    .outline_glyph(glyph.glyph.clone())
+                                    .is_some()
+                            })
+                            .count()
+                    },
+                )
+            })
+            .sum()
+    }
+
+    /// Returns the rectangle of a caret of the given `width`, in pixels,
+    /// placed before the character at `index` of a section, as laid out when
+    /// drawing it.
+    ///
+    /// Characters are counted across all the texts of the section. An `index`
+    /// past the end places the caret after the last character, and the caret
+    /// of an empty section is placed at its position. With a
+    /// [`tab_width`](struct.GlyphBrushBuilder.html#method.tab_width), every
+    /// tab counts as the spaces it expands to.
+    ///
+    /// The caret spans the ascent and descent of the font of the character,
+    /// and can be drawn with [`queue_rect`](#method.queue_rect). Blinking is
+    /// up to the caller.
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    pub fn caret_rect<'a, S>(
+        &mut self,
+        section: S,
+        index: usize,
+        width: f32,
+    ) -> Rect
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let origin = self.settings.origin;
+
+        self.with_glyph_section(&section.into(), |glyph_brush, section| {
+            let glyphs: Vec<_> =
+                glyph_brush.glyphs(&section).cloned().collect();
+            let rect = caret::rect(
+                glyph_brush.fonts(),
+                &section,
+                &glyphs,
+                index,
+                width,
+            );
+
+            origin.rect(rect)
+        })
+    }
+
+    /// Returns the rectangles covering the characters of a section in the
+    /// given `range`, one for every line they span, as laid out when drawing
+    /// it, e.g. to highlight a selection.
+    ///
+    /// Characters are counted like in [`caret_rect`](#method.caret_rect).
+    /// Every rectangle spans the selected glyphs of its line, trailing
+    /// whitespace included, from the highest ascent to the lowest descent of
+    /// their fonts. Empty ranges and lines without any glyph have no
+    /// rectangle. They can be drawn with [`queue_rect`](#method.queue_rect).
+    ///
+    /// Benefits from caching, see [caching behaviour](#caching-behaviour).
+    pub fn selection_rects<'a, S>(
+        &mut self,
+        section: S,
+        range: Range<usize>,
+    ) -> Vec<Rect>
+    where
+        S: Into<Cow<'a, Section<'a>>>,
+    {
+        let origin = self.settings.origin;
+
+        self.with_glyph_section(&section.into(), |glyph_brush, section| {
+            let glyphs: Vec<_> =
+                glyph_brush.glyphs(&section).cloned().collect();
+
+            caret::selection(glyph_brush.fonts(), &section, &glyphs, range)
+                .into_iter()
+                .map(|rect| origin.rect(rect))
+                .collect()
+        })
+    }
+
+    /// Converts the section into a section of the inner `glyph_brush`,
+    /// expanding its tabs if needed, and calls `f` with it.
+    fn with_glyph_section<'b, R>(
+        &'b mut self,
+        section: &Section<'_>,
+        f: impl FnOnce(
+            &'b mut glyph_brush::GlyphBrush<Vertex, GlyphExtra, F, H>,
+            Section<'_, GlyphExtra>,
+        ) -> R,
+    ) -> R {
+        let settings = self.settings;
+
+        let expanded = self
+            .settings
+            .tab_width
+            .and_then(|tab_width| tabs::expand(section, tab_width));
+
+        match expanded {
+            Some(expanded) => f(
+                &mut self.glyph_brush,
+                extra::section(&expanded.to_borrowed(), &settings),
+            ),
+            None => {
+                f(&mut self.glyph_brush, extra::section(section, &settings))
+            }
+        }
+    }
 }
 
 impl<D, F, H> GlyphBrush<D, F, H>
 where
-    F: Font + Sync,
-    H: BuildHasher,
+    F: Font + Clone,
+    H: BuildHasher + Clone,
 {
-    fn process_queued(
+    /// Replaces all the available fonts, returning the
+    /// [`FontId`](struct.FontId.html) of every new font, in order.
+    ///
+    /// The glyph cache is emptied and shrunk back to its initial size, so no
+    /// glyph of the previous fonts lingers in it. Any sections queued
+    /// beforehand are discarded.
+    ///
+    /// Every `FontId` obtained before becomes invalid, as it now refers to
+    /// one of the new fonts, if any. Retained sections keep the ids they were
+    /// created with, so they need to be updated.
+    pub fn set_fonts(
         &mut self,
         device: &wgpu::Device,
-        staging_belt: &mut wgpu::util::StagingBelt,
-        encoder: &mut wgpu::CommandEncoder,
-    ) {
-        let pipeline = &mut self.pipeline;
+        fonts: Vec<F>,
+    ) -> Vec<FontId> {
+        let ids = (0..fonts.len()).map(FontId).collect();
+        let (width, height) = self.initial_cache_size;
 
-        let mut brush_action;
+        self.glyph_brush = self
+            .glyph_brush
+            .to_builder()
+            .replace_fonts(|_| fonts)
+            .initial_cache_size((width, height))
+            .build();
 
-        loop {
-            brush_action = self.glyph_brush.process_queued(
-                |rect, tex_data| {
-                    let offset = [rect.min[0] as u16, rect.min[1] as u16];
-                    let size = [rect.width() as u16, rect.height() as u16];
+        self.pipeline.resize_cache(device, width, height);
 
-                    pipeline.update_cache(
-                        device,
-                        staging_belt,
-                        encoder,
-                        offset,
-                        size,
-                        tex_data,
-                    );
-                },
-                Instance::from_vertex,
-            );
+        if let Some(callback) = &mut self.on_cache_resize {
+            callback(width, height);
+        }
 
-            match brush_action {
-                Ok(_) => break,
-                Err(BrushError::TextureTooSmall { suggested }) => {
-                    // TODO: Obtain max texture dimensions using `wgpu`
-                    // This is currently not possible I think. Ask!
-                    let max_image_dimension = 2048;
-
-                    let (new_width, new_height) = if (suggested.0
-                        > max_image_dimension
-                        || suggested.1 > max_image_dimension)
-                        && (self.glyph_brush.texture_dimensions().0
-                            < max_image_dimension
-                            || self.glyph_brush.texture_dimensions().1
-                                < max_image_dimension)
-                    {
-                        (max_image_dimension, max_image_dimension)
-                    } else {
-                        suggested
-                    };
+        self.backgrounds.clear();
+        self.rects.clear();
+        self.decorations.clear();
+        self.revealed_sections = 0;
 
-                    if log_enabled!(log::Level::Warn) {
-                        warn!(
-                            "Increasing glyph texture size {old:?} -> {new:?}. \
-                             Consider building with `.initial_cache_size({new:?})` to avoid \
-                             resizing",
-                            old = self.glyph_brush.texture_dimensions(),
-                            new = (new_width, new_height),
-                        );
-                    }
+        ids
+    }
 
-                    pipeline.increase_cache_size(device, new_width, new_height);
-                    self.glyph_brush.resize_texture(new_width, new_height);
-                }
-            }
+    /// Adds a copy of the font with the given `font_id`, with its variation
+    /// axes set to the given values (e.g. `(*b"wght", 600.0)` for the weight).
+    ///
+    /// Returns a new [`FontId`](struct.FontId.html) to reference the variation.
+    /// Glyphs are cached by font, so sections using different variations of
+    /// the same font never share entries of the glyph cache. Axes the font
+    /// does not have are ignored.
+    pub fn add_font_variation(
+        &mut self,
+        font_id: FontId,
+        variations: &[([u8; 4], f32)],
+    ) -> FontId
+    where
+        F: ab_glyph::VariableFont,
+    {
+        let mut font = self.glyph_brush.fonts()[font_id.0].clone();
+
+        for (tag, value) in variations {
+            let _ = font.set_variation(tag, *value);
         }
 
-        match brush_action.unwrap() {
-            BrushAction::Draw(verts) => {
-                self.pipeline.upload(device, staging_belt, encoder, &verts);
-            }
-            BrushAction::ReDraw => {}
-        };
+        self.glyph_brush.add_font(font)
     }
 }
 
-impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
-    fn new(
+impl<D, F, H> GlyphBrush<D, F, H>
+where
+    F: Font + Sync,
+    H: BuildHasher,
+{
+    /// Processes all queued sections and uploads the resulting glyphs,
+    /// applying a position transform (e.g. a projection), without drawing
+    /// them.
+    ///
+    /// The processed glyphs can then be recorded into a render pass of your
+    /// own using [`draw_into_pass`](#method.draw_into_pass).
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    pub fn prepare_queued_with_transform(
+        &mut self,
         device: &wgpu::Device,
-        filter_mode: wgpu::FilterMode,
-        render_format: wgpu::TextureFormat,
-        raw_builder: glyph_brush::GlyphBrushBuilder<F, H>,
-    ) -> Self {
-        let glyph_brush = raw_builder.build();
-        let (cache_width, cache_height) = glyph_brush.texture_dimensions();
-        GlyphBrush {
-            pipeline: Pipeline::<()>::new(
-                device,
-                filter_mode,
-                render_format,
-                cache_width,
-                cache_height,
-            ),
-            glyph_brush,
-        }
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        transform: [f32; 16],
+    ) -> Result<DrawResult, String> {
+        let result = self.process_queued(device, staging_belt, encoder)?;
+        self.pipeline.update_transform(
+            device,
+            staging_belt,
+            encoder,
+            transform,
+        );
+
+        Ok(result)
+    }
+
+    /// Processes all queued sections and uploads the resulting glyphs, with
+    /// the orthographic projection of a target of the given size, without
+    /// drawing them.
+    ///
+    /// This separates the two phases of
+    /// [`draw_queued`](struct.GlyphBrush.html#method.draw_queued): the
+    /// rasterization and upload of glyphs can happen earlier in the frame,
+    /// e.g. overlapped with other GPU work, and a following
+    /// [`redraw`](struct.GlyphBrush.html#method.redraw) onto a target of
+    /// the same size only issues the draw, uploading nothing else.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    #[inline]
+    pub fn upload_queued(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target_width: u32,
+        target_height: u32,
+    ) -> Result<DrawResult, String> {
+        self.prepare_queued_with_transform(
+            device,
+            staging_belt,
+            encoder,
+            self.settings.origin.projection(target_width, target_height),
+        )
+    }
+
+    /// Uploads a new position transform (e.g. a projection) without
+    /// processing the queue nor uploading any glyphs.
+    ///
+    /// The transform is used by any subsequent
+    /// [`redraw`](struct.GlyphBrush.html#method.redraw) or
+    /// [`draw_into_pass`](#method.draw_into_pass) recorded into the same
+    /// `encoder`, which allows drawing the same text with a moving camera
+    /// cheaply.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    #[inline]
+    pub fn set_transform(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        transform: [f32; 16],
+    ) {
+        self.pipeline.update_transform(
+            device,
+            staging_belt,
+            encoder,
+            transform,
+        );
+    }
+
+    /// Returns the GPU memory currently held by the `GlyphBrush`, which
+    /// changes as its glyph cache and instance buffers grow.
+    pub fn memory_usage(&self) -> MemoryReport {
+        self.pipeline.memory_usage()
+    }
+
+    /// Returns whether the last draw of the queued sections uploaded
+    /// anything, i.e. whether it drew something different from the previous
+    /// one, or whether the glyph cache was recreated since.
+    ///
+    /// Only [`draw_queued`](#method.draw_queued) and its variants reset it.
+    /// Draws bypassing the queue, like
+    /// [`draw_instances`](#method.draw_instances) and
+    /// [`draw_sprites`](#method.draw_sprites), only set it if they change the
+    /// transform shared with the queued sections.
+    ///
+    /// When it returns `false`, a static frame can be kept as it is instead
+    /// of recording and presenting it again.
+    pub fn needs_redraw(&self) -> bool {
+        self.pipeline.changed()
+    }
+
+    /// Returns how many times the texture of the glyph cache has been
+    /// recreated, e.g. to grow it or to clear it.
+    ///
+    /// Instances holding texture coordinates into the cache, like the ones
+    /// returned by [`glyph_instance`](#method.glyph_instance) or saved with
+    /// [`dump_frame`](#method.dump_frame), are stale once it changes and have
+    /// to be created again.
+    pub fn cache_generation(&self) -> u64 {
+        self.pipeline.cache_generation()
+    }
+
+    /// Returns the last position transform uploaded by a draw call or
+    /// [`set_transform`](#method.set_transform), before it is adjusted to the
+    /// [`origin`](struct.GlyphBrushBuilder.html#method.origin) and flipped by
+    /// [`flip_y`](struct.GlyphBrushBuilder.html#method.flip_y).
+    ///
+    /// Until a transform is uploaded, the identity matrix is used.
+    pub fn transform(&self) -> [f32; 16] {
+        self.pipeline.transform()
+    }
+
+    /// Returns the sampler used to sample the glyph cache, e.g. to sample it
+    /// the same way from a pipeline of your own.
+    pub fn sampler(&self) -> &wgpu::Sampler {
+        self.pipeline.sampler()
+    }
+
+    /// Returns the uniform buffer of the pipeline, bound at binding `0` of
+    /// the layout described by
+    /// [`uniform_layout_entries`](fn.uniform_layout_entries.html).
+    ///
+    /// It holds 36 `f32` values, uploaded before every draw:
+    ///
+    /// - `0..16`: the column-major transform, after adjusting it to the
+    ///   [`origin`](struct.GlyphBrushBuilder.html#method.origin) and flipping
+    ///   it with [`flip_y`](struct.GlyphBrushBuilder.html#method.flip_y).
+    /// - `16..24`: the center, half extents, corner radius and whether the
+    ///   [rounded clip](#method.set_rounded_clip) is enabled.
+    /// - `24..32`: the kind, time and parameters of the
+    ///   [`Effect`](enum.Effect.html).
+    /// - `32..36`: whether a fill texture is enabled, the alpha cutoff, the
+    ///   gamma and the edge softness.
+    ///
+    /// The buffer should only be read, as it is overwritten whenever any of
+    /// these values change.
+    pub fn uniform_buffer(&self) -> &wgpu::Buffer {
+        self.pipeline.uniform_buffer()
+    }
+
+    /// Records the glyphs processed by the last draw or
+    /// [`prepare_queued_with_transform`](#method.prepare_queued_with_transform)
+    /// call into a render pass you own.
+    ///
+    /// This allows text to be interleaved with other geometry in the same
+    /// render pass. The render pass must have a color attachment matching the
+    /// `render_format` provided on creation of the `GlyphBrush` and, if one
+    /// was configured, a compatible depth stencil attachment.
+    #[inline]
+    pub fn draw_into_pass<'pass>(
+        &'pass self,
+        render_pass: &mut wgpu::RenderPass<'pass>,
+    ) {
+        self.pipeline.draw_into_pass(render_pass);
+    }
+
+    /// Builds a render pipeline for render targets of the given format, in
+    /// addition to the one of the `render_format` provided on creation of the
+    /// `GlyphBrush`, so the glyphs can be recorded into render passes with
+    /// attachments of that format using
+    /// [`draw_into_pass_with_format`](#method.draw_into_pass_with_format).
+    ///
+    /// Pipelines are built once per format and kept until the brush is
+    /// [recreated](#method.recreate).
+    pub fn prepare_render_format(
+        &mut self,
+        device: &wgpu::Device,
+        render_format: wgpu::TextureFormat,
+    ) {
+        self.pipeline.prepare_render_format(device, render_format);
+    }
+
+    /// Records the glyphs processed by the last draw or
+    /// [`prepare_queued_with_transform`](#method.prepare_queued_with_transform)
+    /// call into a render pass you own, whose color attachment has the given
+    /// `render_format`.
+    ///
+    /// Unlike [`draw_into_pass`](#method.draw_into_pass), the format may
+    /// differ from the one provided on creation of the `GlyphBrush`, as long
+    /// as a pipeline was built for it with
+    /// [`prepare_render_format`](#method.prepare_render_format). Otherwise,
+    /// an error is returned and nothing is recorded.
+    pub fn draw_into_pass_with_format<'pass>(
+        &'pass self,
+        render_pass: &mut wgpu::RenderPass<'pass>,
+        render_format: wgpu::TextureFormat,
+    ) -> Result<(), String> {
+        self.pipeline
+            .draw_into_pass_with_format(render_pass, render_format)
+    }
+
+    /// Writes the instances processed by the last draw call and its
+    /// transform to a file, to replay them later in isolation with
+    /// `draw_dumped`, e.g. to reproduce a rendering bug.
+    ///
+    /// The glyph cache is not dumped, so the glyphs of a replayed frame
+    /// sample the cache of the brush replaying it, while backgrounds,
+    /// rectangles and decorations are reproduced exactly.
+    ///
+    /// The format is stable. Every value is a little-endian 32-bit word:
+    ///
+    /// - the magic bytes `WGID`,
+    /// - the version of the format, currently `1`,
+    /// - the size of an [`Instance`](struct.Instance.html) in words,
+    /// - the 16 `f32` values of the column-major transform, as returned by
+    ///   [`transform`](#method.transform),
+    /// - the amount of instances,
+    /// - the words of every instance, in the order of its fields.
+    pub fn dump_frame(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(
+            path,
+            dump::encode(&self.uploaded, self.pipeline.transform()),
+        )
+    }
+
+    /// Empties the glyph cache and shrinks it back to its initial size.
+    ///
+    /// Any glyphs drawn afterwards will be rasterized and uploaded again.
+    /// This can be useful to free cache space after a scene transition, when
+    /// the previously drawn text is not going to be needed anymore.
+    pub fn clear_cache(&mut self, device: &wgpu::Device) {
+        let (width, height) = self.initial_cache_size;
+
+        self.pipeline.resize_cache(device, width, height);
+        self.glyph_brush.resize_texture(width, height);
+
+        if let Some(callback) = &mut self.on_cache_resize {
+            callback(width, height);
+        }
+    }
+
+    /// Rasterizes and uploads the given characters to the glyph cache ahead
+    /// of time, without drawing them.
+    ///
+    /// This allows front-loading the rasterization work (e.g. during a loading
+    /// screen) to avoid hitches when the glyphs are first drawn. The glyphs
+    /// are cached at a whole pixel position, so drawing them at a very
+    /// different subpixel position may still need a new rasterization
+    /// depending on the `draw_cache_position_tolerance`.
+    ///
+    /// Any sections queued beforehand are processed and discarded, so this
+    /// should be called before queueing the sections of the current frame.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    pub fn preload_glyphs<S, I>(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        font_id: FontId,
+        scale: S,
+        chars: I,
+    ) -> Result<(), String>
+    where
+        S: Into<PxScale>,
+        I: IntoIterator<Item = char>,
+    {
+        let scale = scale.into();
+        let font = &self.glyph_brush.fonts()[font_id.0];
+
+        let glyphs = chars
+            .into_iter()
+            .map(|c| SectionGlyph {
+                section_index: 0,
+                byte_index: 0,
+                glyph: font
+                    .glyph_id(c)
+                    .with_scale_and_position(scale, point(0.0, 0.0)),
+                font_id,
+            })
+            .collect();
+
+        self.glyph_brush.queue_pre_positioned(
+            glyphs,
+            vec![GlyphExtra::from(Extra::default())],
+            Rect::default(),
+        );
+
+        let action = self
+            .cache_queued(device, staging_belt, encoder)
+            .map(|(action, _)| action);
+
+        self.backgrounds.clear();
+        self.rects.clear();
+        self.decorations.clear();
+        self.revealed_sections = 0;
+
+        if let BrushAction::Draw(_) = action? {
+            // The preloaded glyphs must not be drawn
+            self.pipeline.upload(device, staging_belt, encoder, &[])?;
+        }
+
+        Ok(())
+    }
+
+    /// Rasterizes and uploads a single character to the glyph cache, if
+    /// needed, and returns an [`Instance`](struct.Instance.html) drawing it
+    /// with its baseline origin at the given `position`.
+    ///
+    /// This is useful for very manual layouts, e.g. a text editor drawing its
+    /// characters one by one with
+    /// [`draw_instances`](#method.draw_instances). Returns `None` for
+    /// characters without an outline, like whitespace.
+    ///
+    /// Like [`preload_glyphs`](#method.preload_glyphs), any sections queued
+    /// beforehand are processed and discarded. The returned instance stays
+    /// valid as long as the glyph is kept in the cache, which may evict it
+    /// to make room for other glyphs once it is full.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    #[allow(clippy::too_many_arguments)]
+    pub fn glyph_instance<S>(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        font_id: FontId,
+        c: char,
+        scale: S,
+        position: (f32, f32),
+        color: [f32; 4],
+    ) -> Result<Option<Instance>, String>
+    where
+        S: Into<PxScale>,
+    {
+        let font = &self.glyph_brush.fonts()[font_id.0];
+        let position = self.settings.origin.position(position);
+
+        let glyph = SectionGlyph {
+            section_index: 0,
+            byte_index: 0,
+            glyph: font
+                .glyph_id(c)
+                .with_scale_and_position(scale, point(position.0, position.1)),
+            font_id,
+        };
+
+        if let Some(instances) = self.cache_glyphs(
+            device,
+            staging_belt,
+            encoder,
+            vec![glyph],
+            color,
+        )? {
+            self.last_instances = instances;
+        }
+
+        Ok(self.last_instances.first().copied())
+    }
+
+    /// Rasterizes and uploads the characters of `text` to the glyph cache, if
+    /// needed, and returns the [`Instance`](struct.Instance.html)s drawing
+    /// them along a `path` of straight segments joining the given points.
+    ///
+    /// Glyphs are placed one after another by arc length, with the middle of
+    /// their baseline on the path and rotated to follow its direction.
+    /// Characters past the end of the path and characters without an outline,
+    /// like whitespace, produce no instance.
+    ///
+    /// Like [`glyph_instance`](#method.glyph_instance), any sections queued
+    /// beforehand are processed and discarded, and the instances stay valid
+    /// as long as their glyphs are kept in the cache.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    #[allow(clippy::too_many_arguments)]
+    pub fn text_on_path<S>(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        font_id: FontId,
+        text: &str,
+        scale: S,
+        path: &[(f32, f32)],
+        color: [f32; 4],
+    ) -> Result<Vec<Instance>, String>
+    where
+        S: Into<PxScale>,
+    {
+        let scale = scale.into();
+        let origin = self.settings.origin;
+        let points: Vec<_> =
+            path.iter().map(|&point| origin.position(point)).collect();
+        let path = path::Polyline::new(&points);
+
+        let font = &self.glyph_brush.fonts()[font_id.0];
+        let scaled = font.as_scaled(scale);
+
+        let mut glyphs = Vec::new();
+        let mut rotations = Vec::new();
+        let mut distance = 0.0;
+        let mut previous = None;
+
+        for (byte_index, c) in text.char_indices() {
+            let id = scaled.glyph_id(c);
+
+            if let Some(previous) = previous {
+                distance += scaled.kern(previous, id);
+            }
+
+            let advance = scaled.h_advance(id);
+            let middle = distance + advance / 2.0;
+
+            previous = Some(id);
+            distance += advance;
+
+            let ((x, y), angle) = match path.at(middle) {
+                Some(position) => position,
+                None => break,
+            };
+
+            let glyph =
+                id.with_scale_and_position(scale, point(x - advance / 2.0, y));
+
+            // Only glyphs with an outline produce an instance
+            if font.outline_glyph(glyph.clone()).is_none() {
+                continue;
+            }
+
+            glyphs.push(SectionGlyph {
+                section_index: 0,
+                byte_index,
+                glyph,
+                font_id,
+            });
+            rotations.push(((x, y), angle));
+        }
+
+        if let Some(instances) =
+            self.cache_glyphs(device, staging_belt, encoder, glyphs, color)?
+        {
+            self.last_instances = instances
+                .into_iter()
+                .zip(rotations)
+                .enumerate()
+                .map(|(index, (instance, (pivot, angle)))| {
+                    instance
+                        .with_rotation(pivot, angle)
+                        .with_index(index as u32)
+                })
+                .collect();
+        }
+
+        Ok(self.last_instances.clone())
+    }
+
+    /// Rasterizes and uploads the given glyphs to the glyph cache, if needed,
+    /// discarding any queued sections.
+    ///
+    /// Returns the instances of the glyphs, in order, or `None` if the same
+    /// glyphs were cached last time.
+    fn cache_glyphs(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        glyphs: Vec<SectionGlyph>,
+        color: [f32; 4],
+    ) -> Result<Option<Vec<Instance>>, String> {
+        // The glyphs must not be cropped
+        let bounds = Rect {
+            min: point(f32::MIN, f32::MIN),
+            max: point(f32::MAX, f32::MAX),
+        };
+
+        let extra = vec![
+            GlyphExtra::from(Extra {
+                color,
+                ..Extra::default()
+            });
+            glyphs.len()
+        ];
+
+        self.glyph_brush.queue_pre_positioned(glyphs, extra, bounds);
+
+        let action = self
+            .cache_queued(device, staging_belt, encoder)
+            .map(|(action, _)| action);
+
+        self.backgrounds.clear();
+        self.rects.clear();
+        self.decorations.clear();
+        self.revealed_sections = 0;
+
+        match action? {
+            BrushAction::Draw(verts) => {
+                // The glyphs are only drawn by the caller
+                self.pipeline.upload(device, staging_belt, encoder, &[])?;
+
+                Ok(Some(
+                    verts.into_iter().map(|vertex| vertex.instance).collect(),
+                ))
+            }
+            BrushAction::ReDraw => Ok(None),
+        }
+    }
+
+    /// Clips all the text drawn from now on to the given rounded rectangle,
+    /// with antialiased edges, or stops clipping it if `None`.
+    ///
+    /// Unlike a scissoring region, the clip is applied by the shader, so its
+    /// corners can be rounded. It is applied the next time glyphs are
+    /// processed, so it does not affect
+    /// [`redraw`](struct.GlyphBrush.html#method.redraw).
+    pub fn set_rounded_clip(&mut self, clip: Option<RoundedClip>) {
+        self.pipeline.set_clip(clip);
+    }
+
+    /// Scissors everything drawn from now on to the given region of the
+    /// render target, or stops scissoring it if `None`, e.g. to keep text
+    /// within a fixed content area.
+    ///
+    /// It applies to every draw, including
+    /// [`redraw`](struct.GlyphBrush.html#method.redraw), until it is changed.
+    /// The region of draws taking one of their own, like
+    /// [`draw_queued_with_transform_and_scissoring`](struct.GlyphBrush.html#method.draw_queued_with_transform_and_scissoring),
+    /// is intersected with it. Like any scissoring region, it must be within
+    /// the bounds of the render target.
+    pub fn set_clip_bounds(&mut self, clip_bounds: Option<Region>) {
+        self.pipeline.set_clip_bounds(clip_bounds);
+    }
+
+    /// Draws everything from now on into the given
+    /// [`Viewport`](struct.Viewport.html) of the render target, or into the
+    /// whole render target if `None`, e.g. to draw a label scaled down into a
+    /// corner.
+    ///
+    /// Like the clip bounds, it applies to every draw, including
+    /// [`redraw`](struct.GlyphBrush.html#method.redraw), until it is changed.
+    /// It is not applied when drawing into a render pass of your own, where
+    /// you can set it yourself.
+    pub fn set_viewport(&mut self, viewport: Option<Viewport>) {
+        self.pipeline.set_viewport(viewport);
+    }
+
+    /// Registers a callback invoked with the new width and height of the
+    /// glyph cache every time its texture is recreated, replacing the
+    /// previous one.
+    ///
+    /// The texture is recreated when the cache grows to fit the glyphs
+    /// queued, when it is cleared, when the fonts are replaced and when the
+    /// brush is recreated, so anything holding on to the texture can refresh
+    /// it.
+    pub fn on_cache_resize(
+        &mut self,
+        callback: impl FnMut(u32, u32) + Send + Sync + 'static,
+    ) {
+        self.on_cache_resize = Some(Box::new(callback));
+    }
+
+    /// Applies an animated [`Effect`](enum.Effect.html) to all the glyphs
+    /// drawn from now on, or stops animating them if `None`.
+    ///
+    /// Like the rounded clip, it is applied the next time glyphs are
+    /// processed.
+    pub fn set_effect(&mut self, effect: Option<Effect>) {
+        self.pipeline.set_effect(effect);
+    }
+
+    /// Sets the time driving the [`Effect`](enum.Effect.html) of the
+    /// `GlyphBrush`, in any unit (e.g. seconds since startup).
+    ///
+    /// Changing the time only updates a uniform, so the glyphs are not
+    /// processed nor uploaded again because of it.
+    pub fn set_time(&mut self, time: f32) {
+        self.pipeline.set_time(time);
+    }
+
+    /// Fades in the glyphs drawn from now on one after another, or stops
+    /// fading them if `None`.
+    ///
+    /// The `progress` is measured in glyphs: every glyph is transparent until
+    /// the progress reaches its index within its section, and fully opaque
+    /// once it goes past it by one. Increasing the progress over time reveals
+    /// every section glyph by glyph, smoothly. Backgrounds, rectangles and
+    /// decorations are not faded.
+    ///
+    /// Like the time, changing the progress only updates a uniform. Unlike
+    /// [`Style::reveal`](struct.Style.html#structfield.reveal), the faded
+    /// glyphs are still drawn.
+    pub fn set_reveal_progress(&mut self, progress: Option<f32>) {
+        self.pipeline.set_fade_progress(progress);
+    }
+
+    /// Changes the filter method used to sample the glyph cache.
+    ///
+    /// Only the sampler is replaced, so the glyph cache and the render
+    /// pipeline are kept as they are.
+    pub fn set_texture_filter_method(
+        &mut self,
+        device: &wgpu::Device,
+        filter_method: wgpu::FilterMode,
+    ) {
+        self.pipeline
+            .set_texture_filter_method(device, filter_method);
+        self.settings.texture_filter_method = filter_method;
+    }
+
+    /// Changes the texture format of the render targets the `GlyphBrush`
+    /// draws onto, e.g. after reconfiguring a surface.
+    ///
+    /// Only the render pipeline is created again, so the glyph cache and
+    /// the uploaded glyphs are kept, and a following
+    /// [`redraw`](#method.redraw) can use a target with the new format.
+    pub fn set_render_format(
+        &mut self,
+        device: &wgpu::Device,
+        render_format: wgpu::TextureFormat,
+    ) {
+        self.pipeline.set_render_format(device, render_format);
+    }
+
+    /// Changes how the render target is loaded at the start of every render
+    /// pass used to draw glyphs. See
+    /// [`GlyphBrushBuilder::load_op`](struct.GlyphBrushBuilder.html#method.load_op).
+    pub fn set_load_op(&mut self, load_op: wgpu::LoadOp<wgpu::Color>) {
+        self.pipeline.set_load_op(load_op);
+        self.settings.load_op = load_op;
+    }
+
+    /// Fills the glyphs drawn from now on with a texture, multiplying their
+    /// color by it, or goes back to flat colors if `None`.
+    ///
+    /// The texture is repeated across the render target in physical pixels,
+    /// so it does not move with the text. Backgrounds and decorations are not
+    /// filled. Sprite fonts keep the fill texture they were created with.
+    ///
+    /// Like the rounded clip, it is applied the next time glyphs are
+    /// processed.
+    pub fn set_fill_texture(
+        &mut self,
+        device: &wgpu::Device,
+        texture: Option<wgpu::TextureView>,
+    ) {
+        self.pipeline.set_fill_texture(device, texture);
+    }
+
+    /// Creates a [`SpriteFont`](struct.SpriteFont.html) out of a texture with
+    /// pre-rendered glyphs, e.g. a bitmap font without scalable outlines.
+    ///
+    /// The coverage of the glyphs is read from the red channel of the
+    /// texture, like the glyph cache, and it is sampled with the same
+    /// sampler. The texture must therefore have a float sample type.
+    ///
+    /// `line_height` is the distance between consecutive lines, in pixels.
+    ///
+    /// The font shares the uniforms of the `GlyphBrush`, so it has to be
+    /// created again after [`recreate`](#method.recreate).
+    pub fn create_sprite_font(
+        &self,
+        device: &wgpu::Device,
+        texture: &wgpu::TextureView,
+        glyphs: HashMap<char, SpriteGlyph>,
+        line_height: f32,
+    ) -> SpriteFont {
+        SpriteFont::new(
+            self.pipeline.create_bind_group(device, texture),
+            glyphs,
+            line_height,
+        )
+    }
+
+    /// Recreates all the GPU resources of the `GlyphBrush` using a new
+    /// `wgpu::Device`, keeping its configuration.
+    ///
+    /// This allows the `GlyphBrush` to survive a device loss. The glyph cache
+    /// keeps its current size, but it starts empty. Glyphs will be rasterized
+    /// and uploaded again lazily, the next time they are drawn. The fill
+    /// texture belongs to the old device, so it is removed, and so is the bind
+    /// group layout provided with
+    /// [`GlyphBrushBuilder::uniform_layout`](struct.GlyphBrushBuilder.html#method.uniform_layout),
+    /// which is replaced with one of its own.
+    pub fn recreate(&mut self, device: &wgpu::Device) {
+        let (width, height) = self.glyph_brush.texture_dimensions();
+
+        self.pipeline.recreate(device, width, height);
+        self.glyph_brush.resize_texture(width, height);
+
+        if let Some(callback) = &mut self.on_cache_resize {
+            callback(width, height);
+        }
+    }
+
+    fn process_queued(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Result<DrawResult, String> {
+        let settings = self.settings;
+        let tab_width = settings.tab_width;
+        self.pipeline.reset_changes();
+
+        for section in self.retained.sections() {
+            let section = section.to_borrowed();
+
+            match tab_width
+                .and_then(|tab_width| tabs::expand(&section, tab_width))
+            {
+                Some(expanded) => self
+                    .glyph_brush
+                    .queue(extra::section(&expanded.to_borrowed(), &settings)),
+                None => {
+                    self.glyph_brush.queue(extra::section(&section, &settings))
+                }
+            }
+        }
+
+        if let Some(batch) = &self.batch {
+            for (section, layout) in batch.values() {
+                match layout {
+                    Some(layout) => self
+                        .glyph_brush
+                        .keep_cached_custom_layout(section, layout),
+                    None => self.glyph_brush.keep_cached(section),
+                }
+            }
+        }
+
+        let action = self.cache_queued(device, staging_belt, encoder);
+        self.revealed_sections = 0;
+
+        if action.is_ok() {
+            self.processed_generation = self.pipeline.cache_generation();
+        }
+
+        match action {
+            Ok((BrushAction::Draw(verts), result)) => {
+                // Skip glyphs that were cropped out of their bounds or that
+                // are not revealed
+                let mut verts = Vertex::into_instances(verts);
+
+                // Backgrounds and rectangles are drawn behind every glyph
+                self.uploaded.clear();
+                self.uploaded.append(&mut self.backgrounds);
+
+                let rects = self.uploaded.len();
+                self.uploaded.append(&mut self.rects);
+                self.uploaded_rects = rects..self.uploaded.len();
+
+                self.uploaded.append(&mut verts);
+                self.uploaded.append(&mut self.decorations);
+
+                self.upload_instances(device, staging_belt, encoder)?;
+
+                Ok(result)
+            }
+            Ok((BrushAction::ReDraw, result)) => {
+                self.backgrounds.clear();
+                self.decorations.clear();
+
+                // The rectangles are not known to `glyph_brush`, so they may
+                // have changed even if the sections did not
+                let rects_changed = self.uploaded[self.uploaded_rects.clone()]
+                    != self.rects[..];
+
+                if rects_changed {
+                    let start = self.uploaded_rects.start;
+                    let _ = self.uploaded.splice(
+                        self.uploaded_rects.clone(),
+                        self.rects.iter().copied(),
+                    );
+                    self.uploaded_rects = start..start + self.rects.len();
+                }
+
+                // Glyphs deferred by the upload budget may be uploaded now
+                if rects_changed || self.deferred {
+                    self.upload_instances(device, staging_belt, encoder)?;
+                }
+
+                self.rects.clear();
+
+                Ok(result)
+            }
+            Err(error) => {
+                self.backgrounds.clear();
+                self.rects.clear();
+                self.decorations.clear();
+
+                Err(error)
+            }
+        }
+    }
+
+    /// Processes the queue again if the glyph cache was recreated since it was
+    /// last processed, which dropped the glyphs it processed, keeping the
+    /// transform uploaded.
+    fn process_stale(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Result<(), String> {
+        if self.processed_generation == self.pipeline.cache_generation() {
+            return Ok(());
+        }
+
+        let _ = self.process_queued(device, staging_belt, encoder)?;
+
+        Ok(())
+    }
+
+    /// Uploads the instances of the last processed queue, leaving out the
+    /// glyphs still waiting to be uploaded to the cache, if any.
+    ///
+    /// If they exceed the maximum amount of instances, nothing is drawn and
+    /// the upload is attempted again on the next draw.
+    fn upload_instances(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Result<(), String> {
+        self.deferred = self.pipeline.has_pending_uploads();
+
+        let result = if self.deferred {
+            let pipeline = &self.pipeline;
+            let uploaded: Vec<Instance> = self
+                .uploaded
+                .iter()
+                .filter(|instance| pipeline.is_uploaded(instance))
+                .copied()
+                .collect();
+
+            self.pipeline
+                .upload(device, staging_belt, encoder, &uploaded)
+        } else {
+            self.pipeline
+                .upload(device, staging_belt, encoder, &self.uploaded)
+        };
+
+        if result.is_err() {
+            self.deferred = true;
+        }
+
+        result
+    }
+
+    fn cache_queued(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Result<(BrushAction<Vertex>, DrawResult), String> {
+        let pipeline = &mut self.pipeline;
+        let crop_to_bounds = self.settings.crop_to_bounds;
+        let cache_growth = self.settings.cache_growth;
+        let max_cache_resizes = self.settings.max_cache_resizes;
+        let growth_policy = self.settings.growth_policy;
+        let antialiasing = self.settings.antialiasing;
+        let max_dimension = device.limits().max_texture_dimension_2d;
+
+        let mut brush_action;
+        let mut cache_resizes = 0;
+        let mut glyphs_uploaded = 0;
+
+        loop {
+            brush_action = self.glyph_brush.process_queued(
+                |rect, tex_data| {
+                    let offset = [rect.min[0] as u16, rect.min[1] as u16];
+                    let size = [rect.width() as u16, rect.height() as u16];
+
+                    if antialiasing {
+                        pipeline.update_cache(offset, size, tex_data);
+                    } else {
+                        let hard: Vec<u8> = tex_data
+                            .iter()
+                            .map(
+                                |&coverage| {
+                                    if coverage < 128 {
+                                        0
+                                    } else {
+                                        255
+                                    }
+                                },
+                            )
+                            .collect();
+
+                        pipeline.update_cache(offset, size, &hard);
+                    }
+                },
+                |vertex| Vertex {
+                    reveal: vertex.extra.reveal,
+                    section: vertex.extra.section,
+                    instance: Instance::from_glyph_vertex(
+                        vertex,
+                        crop_to_bounds,
+                    ),
+                },
+            );
+
+            // Glyphs uploaded before growing the cache count towards the
+            // budget of the draw too
+            glyphs_uploaded += pipeline.flush_cache(
+                device,
+                staging_belt,
+                encoder,
+                glyphs_uploaded,
+            );
+
+            match brush_action {
+                Ok(_) => break,
+                Err(BrushError::TextureTooSmall { .. }) if !cache_growth => {
+                    return Err(format!(
+                        "The glyphs queued do not fit in the glyph cache \
+                         {:?} and its growth is disabled",
+                        self.glyph_brush.texture_dimensions(),
+                    ));
+                }
+                Err(BrushError::TextureTooSmall { .. })
+                    if Some(cache_resizes) == max_cache_resizes =>
+                {
+                    return Err(format!(
+                        "The glyphs queued do not fit in the glyph cache \
+                         {:?} after growing it {} times",
+                        self.glyph_brush.texture_dimensions(),
+                        cache_resizes,
+                    ));
+                }
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    let current = self.glyph_brush.texture_dimensions();
+                    let (new_width, new_height) = growth_policy
+                        .next_size(current, suggested, max_dimension)
+                        .ok_or_else(|| {
+                            format!(
+                                "The glyphs queued do not fit in the glyph \
+                                 cache {:?}, which cannot grow beyond the \
+                                 maximum texture dimension {} of the device",
+                                current, max_dimension,
+                            )
+                        })?;
+
+                    if log_enabled!(log::Level::Warn) {
+                        warn!(
+                            "Increasing glyph texture size {old:?} -> {new:?}. \
+                             Consider building with `.initial_cache_size({new:?})` to avoid \
+                             resizing",
+                            old = current,
+                            new = (new_width, new_height),
+                        );
+                    }
+
+                    pipeline.resize_cache(device, new_width, new_height);
+                    self.glyph_brush.resize_texture(new_width, new_height);
+                    cache_resizes += 1;
+
+                    if let Some(callback) = &mut self.on_cache_resize {
+                        callback(new_width, new_height);
+                    }
+                }
+            }
+        }
+
+        let result = DrawResult {
+            cache_grew: cache_resizes > 0,
+            glyphs_uploaded,
+        };
+
+        Ok((brush_action.unwrap(), result))
+    }
+}
+
+impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
+    fn new(
+        device: &wgpu::Device,
+        render_format: wgpu::TextureFormat,
+        settings: Settings,
+        uniform_layout: Option<wgpu::BindGroupLayout>,
+        raw_builder: glyph_brush::GlyphBrushBuilder<F, H>,
+    ) -> Self {
+        let glyph_brush = raw_builder.build();
+        let (cache_width, cache_height) = glyph_brush.texture_dimensions();
+        GlyphBrush {
+            pipeline: Pipeline::<()>::new(
+                device,
+                render_format,
+                &settings,
+                uniform_layout,
+                cache_width,
+                cache_height,
+            ),
+            glyph_brush,
+            initial_cache_size: (cache_width, cache_height),
+            retained: Retained::default(),
+            backgrounds: Vec::new(),
+            rects: Vec::new(),
+            uploaded: Vec::new(),
+            uploaded_rects: 0..0,
+            deferred: false,
+            processed_generation: 0,
+            last_instances: Vec::new(),
+            decorations: Vec::new(),
+            revealed_sections: 0,
+            batch: None,
+            on_cache_resize: None,
+            settings,
+        }
+    }
+
+    /// Draws all queued sections onto a render target.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    #[inline]
+    pub fn draw_queued(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        target_width: u32,
+        target_height: u32,
+    ) -> Result<DrawResult, String> {
+        self.draw_queued_with_transform(
+            device,
+            staging_belt,
+            encoder,
+            target,
+            self.settings.origin.projection(target_width, target_height),
+        )
+    }
+
+    /// Draws all queued sections onto a new texture of the given size,
+    /// cleared to the `background` color first, and returns it.
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// This is useful to bake text into sprites, like labels drawn many
+    /// times. The texture has the `render_format` of the `GlyphBrush` and
+    /// can be rendered to, sampled and copied from.
+    ///
+    /// A fully transparent `background`, i.e. `wgpu::Color::TRANSPARENT`,
+    /// keeps the coverage of the glyphs in the alpha channel. Glyphs are
+    /// blended over the background, so their color channels end up
+    /// multiplied by their alpha in that case, whether or not
+    /// [`premultiplied_output`](struct.GlyphBrushBuilder.html#method.premultiplied_output)
+    /// is enabled: the texture holds premultiplied alpha and must be
+    /// composited with `wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING`.
+    /// An opaque `background` produces an opaque texture.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_to_texture(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        width: u32,
+        height: u32,
+        background: wgpu::Color,
+    ) -> Result<wgpu::Texture, String> {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("wgpu_glyph::draw_to_texture target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.pipeline.render_format(),
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        self.pipeline.set_load_op(wgpu::LoadOp::Clear(background));
+
+        let result = self.draw_queued(
+            device,
+            staging_belt,
+            encoder,
+            &view,
+            width,
+            height,
+        );
+
+        self.pipeline.set_load_op(self.settings.load_op);
+
+        let _ = result?;
+
+        Ok(texture)
+    }
+
+    /// Draws all queued sections onto a render target, applying a position
+    /// transform (e.g. a projection).
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    #[inline]
+    pub fn draw_queued_with_transform(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        transform: [f32; 16],
+    ) -> Result<DrawResult, String> {
+        let result = self.process_queued(device, staging_belt, encoder)?;
+        self.pipeline.draw(
+            device,
+            staging_belt,
+            encoder,
+            target,
+            transform,
+            None,
+        );
+
+        Ok(result)
+    }
+
+    /// Draws all queued sections onto a render target once per provided
+    /// position transform (e.g. to draw the same text in multiple places).
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// The queued sections are only processed and uploaded once. However,
+    /// every transform is uploaded and drawn in its own render pass, so the
+    /// cost still grows linearly with the amount of transforms. Indexing an
+    /// array of transforms in the shader would allow a single draw call, but
+    /// it would also limit the amount of transforms to what fits in a uniform
+    /// buffer.
+    ///
+    /// Only the first render pass uses the
+    /// [`load_op`](struct.GlyphBrushBuilder.html#method.load_op) of the
+    /// `GlyphBrush`. The following ones load the target, so they do not clear
+    /// what the previous ones drew.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    pub fn draw_queued_with_transforms(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        transforms: &[[f32; 16]],
+    ) -> Result<DrawResult, String> {
+        let result = self.process_queued(device, staging_belt, encoder)?;
+        let load_op = self.pipeline.load_op();
+
+        for (i, transform) in transforms.iter().enumerate() {
+            if i == 1 {
+                self.pipeline.set_load_op(wgpu::LoadOp::Load);
+            }
+
+            self.pipeline.draw(
+                device,
+                staging_belt,
+                encoder,
+                target,
+                *transform,
+                None,
+            );
+        }
+
+        self.pipeline.set_load_op(load_op);
+
+        Ok(result)
+    }
+
+    /// Draws the given instances onto a render target, applying a position
+    /// transform (e.g. a projection).
+    ///
+    /// This bypasses the queue and the layout logic completely, which is
+    /// useful if you have already computed the glyph positions and texture
+    /// coordinates yourself. The texture coordinates of the instances refer
+    /// to the glyph cache of this `GlyphBrush`.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    pub fn draw_instances(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        transform: [f32; 16],
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        self.pipeline.draw_instances(
+            device,
+            staging_belt,
+            encoder,
+            target,
+            transform,
+            instances,
+        )
     }
 
-    /// Draws all queued sections onto a render target.
-    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    /// Draws a frame written with [`dump_frame`](#method.dump_frame) onto a
+    /// render target, applying its transform.
     ///
-    /// It __does not__ submit the encoder command buffer to the device queue.
+    /// Like [`draw_instances`](#method.draw_instances), it bypasses the
+    /// queue, and the glyphs sample the glyph cache of this `GlyphBrush`.
     ///
-    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    /// It __does not__ submit the encoder command buffer to the device queue.
     ///
     /// # Panics
     /// Panics if the provided `target` has a texture format that does not match
     /// the `render_format` provided on creation of the `GlyphBrush`.
-    #[inline]
-    pub fn draw_queued(
+    pub fn draw_dumped(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
+        path: impl AsRef<Path>,
         target: &wgpu::TextureView,
-        target_width: u32,
-        target_height: u32,
     ) -> Result<(), String> {
-        self.draw_queued_with_transform(
+        let bytes = std::fs::read(path).map_err(|error| {
+            format!("The dump could not be read: {}", error)
+        })?;
+        let (instances, transform) = dump::decode(&bytes)?;
+
+        self.draw_instances(
             device,
             staging_belt,
             encoder,
             target,
-            orthographic_projection(target_width, target_height),
+            transform,
+            &instances,
         )
     }
 
-    /// Draws all queued sections onto a render target, applying a position
-    /// transform (e.g. a projection).
-    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    /// Draws instances laid out with a [`SpriteFont`](struct.SpriteFont.html)
+    /// onto a render target, applying a position transform (e.g. a
+    /// projection).
     ///
-    /// It __does not__ submit the encoder command buffer to the device queue.
+    /// Like [`draw_instances`](#method.draw_instances), it bypasses the
+    /// queue, but the texture coordinates of the instances refer to the
+    /// texture of the `font`.
     ///
-    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    /// It __does not__ submit the encoder command buffer to the device queue.
     ///
     /// # Panics
     /// Panics if the provided `target` has a texture format that does not match
     /// the `render_format` provided on creation of the `GlyphBrush`.
-    #[inline]
-    pub fn draw_queued_with_transform(
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_sprites(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
         target: &wgpu::TextureView,
         transform: [f32; 16],
+        font: &SpriteFont,
+        instances: &[Instance],
     ) -> Result<(), String> {
-        self.process_queued(device, staging_belt, encoder);
-        self.pipeline.draw(
+        self.pipeline.draw_sprites(
             device,
             staging_belt,
             encoder,
             target,
             transform,
-            None,
-        );
-
-        Ok(())
+            &font.bind_group,
+            instances,
+        )
     }
 
     /// Draws all queued sections onto a render target, applying a position
@@ -312,8 +2083,8 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
         target: &wgpu::TextureView,
         transform: [f32; 16],
         region: Region,
-    ) -> Result<(), String> {
-        self.process_queued(device, staging_belt, encoder);
+    ) -> Result<DrawResult, String> {
+        let result = self.process_queued(device, staging_belt, encoder)?;
         self.pipeline.draw(
             device,
             staging_belt,
@@ -323,6 +2094,69 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
             Some(region),
         );
 
+        Ok(result)
+    }
+
+    /// Draws the glyphs processed by the last draw or
+    /// [`upload_queued`](#method.upload_queued) call again onto a render
+    /// target, reusing its transform.
+    ///
+    /// Unlike [`draw_queued`](#method.draw_queued), it does not process the
+    /// queue nor upload anything. This makes it a cheap way of drawing the
+    /// same text again, e.g. onto a different render target.
+    ///
+    /// The glyphs processed are dropped when the glyph cache is recreated,
+    /// e.g. when it is cleared, since they point to glyphs it no longer
+    /// holds. The queue is then processed again before drawing, which lays
+    /// out and uploads the [retained](#method.retain) sections again, along
+    /// with any sections queued since. Other sections are not drawn until
+    /// they are queued again.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    #[inline]
+    pub fn redraw(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+    ) -> Result<(), String> {
+        self.process_stale(device, staging_belt, encoder)?;
+        self.pipeline.redraw(encoder, target, None);
+
+        Ok(())
+    }
+
+    /// Draws the glyphs processed by the last draw call again onto a dirty
+    /// region of a render target, reusing its transform.
+    ///
+    /// Like [`redraw`](#method.redraw), it does not process the queue nor
+    /// upload anything, unless the glyph cache was recreated since. Any
+    /// fragments outside of the `dirty` region are scissored. This is useful
+    /// for mostly static interfaces where only a small part of the target
+    /// needs to be updated.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    #[inline]
+    pub fn redraw_region(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        dirty: Region,
+    ) -> Result<(), String> {
+        self.process_stale(device, staging_belt, encoder)?;
+        self.pipeline.redraw(encoder, target, Some(dirty));
+
         Ok(())
     }
 }
@@ -330,9 +2164,10 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<(), F, H> {
 impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
     fn new(
         device: &wgpu::Device,
-        filter_mode: wgpu::FilterMode,
         render_format: wgpu::TextureFormat,
         depth_stencil_state: wgpu::DepthStencilState,
+        settings: Settings,
+        uniform_layout: Option<wgpu::BindGroupLayout>,
         raw_builder: glyph_brush::GlyphBrushBuilder<F, H>,
     ) -> Self {
         let glyph_brush = raw_builder.build();
@@ -340,13 +2175,28 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
         GlyphBrush {
             pipeline: Pipeline::<wgpu::DepthStencilState>::new(
                 device,
-                filter_mode,
                 render_format,
                 depth_stencil_state,
+                &settings,
+                uniform_layout,
                 cache_width,
                 cache_height,
             ),
             glyph_brush,
+            initial_cache_size: (cache_width, cache_height),
+            retained: Retained::default(),
+            backgrounds: Vec::new(),
+            rects: Vec::new(),
+            uploaded: Vec::new(),
+            uploaded_rects: 0..0,
+            deferred: false,
+            processed_generation: 0,
+            last_instances: Vec::new(),
+            decorations: Vec::new(),
+            revealed_sections: 0,
+            batch: None,
+            on_cache_resize: None,
+            settings,
         }
     }
 
@@ -361,6 +2211,7 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
     /// Panics if the provided `target` has a texture format that does not match
     /// the `render_format` provided on creation of the `GlyphBrush`.
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_queued(
         &mut self,
         device: &wgpu::Device,
@@ -370,14 +2221,14 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
         depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
         target_width: u32,
         target_height: u32,
-    ) -> Result<(), String> {
+    ) -> Result<DrawResult, String> {
         self.draw_queued_with_transform(
             device,
             staging_belt,
             encoder,
             target,
             depth_stencil_attachment,
-            orthographic_projection(target_width, target_height),
+            self.settings.origin.projection(target_width, target_height),
         )
     }
 
@@ -401,8 +2252,8 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
         target: &wgpu::TextureView,
         depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
         transform: [f32; 16],
-    ) -> Result<(), String> {
-        self.process_queued(device, staging_belt, encoder);
+    ) -> Result<DrawResult, String> {
+        let result = self.process_queued(device, staging_belt, encoder)?;
         self.pipeline.draw(
             device,
             staging_belt,
@@ -413,7 +2264,181 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
             None,
         );
 
-        Ok(())
+        Ok(result)
+    }
+
+    /// Draws all queued sections onto a render target once per provided
+    /// position transform (e.g. to draw the same text in multiple places).
+    /// See [`queue`](struct.GlyphBrush.html#method.queue).
+    ///
+    /// The queued sections are only processed and uploaded once. However,
+    /// every transform is uploaded and drawn in its own render pass, so the
+    /// cost still grows linearly with the amount of transforms. Indexing an
+    /// array of transforms in the shader would allow a single draw call, but
+    /// it would also limit the amount of transforms to what fits in a uniform
+    /// buffer.
+    ///
+    /// Only the first render pass uses the
+    /// [`load_op`](struct.GlyphBrushBuilder.html#method.load_op) of the
+    /// `GlyphBrush` and the load operations of the `depth_stencil_attachment`.
+    /// The following ones load the target and the depth stencil attachment, so
+    /// they do not clear what the previous ones drew.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// Trims the cache, see [caching behaviour](#caching-behaviour).
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    pub fn draw_queued_with_transforms(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        transforms: &[[f32; 16]],
+    ) -> Result<DrawResult, String> {
+        let result = self.process_queued(device, staging_belt, encoder)?;
+        let load_op = self.pipeline.load_op();
+        let mut depth_stencil_attachment = depth_stencil_attachment;
+
+        for (i, transform) in transforms.iter().enumerate() {
+            if i == 1 {
+                self.pipeline.set_load_op(wgpu::LoadOp::Load);
+
+                if let Some(ops) = &mut depth_stencil_attachment.depth_ops {
+                    ops.load = wgpu::LoadOp::Load;
+                }
+
+                if let Some(ops) = &mut depth_stencil_attachment.stencil_ops {
+                    ops.load = wgpu::LoadOp::Load;
+                }
+            }
+
+            self.pipeline.draw(
+                device,
+                staging_belt,
+                encoder,
+                target,
+                depth_stencil_attachment.clone(),
+                *transform,
+                None,
+            );
+        }
+
+        self.pipeline.set_load_op(load_op);
+
+        Ok(result)
+    }
+
+    /// Draws the given instances onto a render target, applying a position
+    /// transform (e.g. a projection).
+    ///
+    /// This bypasses the queue and the layout logic completely, which is
+    /// useful if you have already computed the glyph positions and texture
+    /// coordinates yourself. The texture coordinates of the instances refer
+    /// to the glyph cache of this `GlyphBrush`.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_instances(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        transform: [f32; 16],
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        self.pipeline.draw_instances(
+            device,
+            staging_belt,
+            encoder,
+            target,
+            depth_stencil_attachment,
+            transform,
+            instances,
+        )
+    }
+
+    /// Draws a frame written with [`dump_frame`](#method.dump_frame) onto a
+    /// render target, applying its transform.
+    ///
+    /// Like [`draw_instances`](#method.draw_instances), it bypasses the
+    /// queue, and the glyphs sample the glyph cache of this `GlyphBrush`.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    pub fn draw_dumped(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        path: impl AsRef<Path>,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+    ) -> Result<(), String> {
+        let bytes = std::fs::read(path).map_err(|error| {
+            format!("The dump could not be read: {}", error)
+        })?;
+        let (instances, transform) = dump::decode(&bytes)?;
+
+        self.draw_instances(
+            device,
+            staging_belt,
+            encoder,
+            target,
+            depth_stencil_attachment,
+            transform,
+            &instances,
+        )
+    }
+
+    /// Draws instances laid out with a [`SpriteFont`](struct.SpriteFont.html)
+    /// onto a render target, applying a position transform (e.g. a
+    /// projection).
+    ///
+    /// Like [`draw_instances`](#method.draw_instances), it bypasses the
+    /// queue, but the texture coordinates of the instances refer to the
+    /// texture of the `font`.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_sprites(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        transform: [f32; 16],
+        font: &SpriteFont,
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        self.pipeline.draw_sprites(
+            device,
+            staging_belt,
+            encoder,
+            target,
+            depth_stencil_attachment,
+            transform,
+            &font.bind_group,
+            instances,
+        )
     }
 
     /// Draws all queued sections onto a render target, applying a position
@@ -428,6 +2453,7 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
     /// Panics if the provided `target` has a texture format that does not match
     /// the `render_format` provided on creation of the `GlyphBrush`.
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_queued_with_transform_and_scissoring(
         &mut self,
         device: &wgpu::Device,
@@ -437,8 +2463,8 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
         depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
         transform: [f32; 16],
         region: Region,
-    ) -> Result<(), String> {
-        self.process_queued(device, staging_belt, encoder);
+    ) -> Result<DrawResult, String> {
+        let result = self.process_queued(device, staging_belt, encoder)?;
 
         self.pipeline.draw(
             device,
@@ -450,13 +2476,85 @@ impl<F: Font + Sync, H: BuildHasher> GlyphBrush<wgpu::DepthStencilState, F, H> {
             Some(region),
         );
 
+        Ok(result)
+    }
+
+    /// Draws the glyphs processed by the last draw or
+    /// [`upload_queued`](#method.upload_queued) call again onto a render
+    /// target, reusing its transform.
+    ///
+    /// Unlike [`draw_queued`](#method.draw_queued-1), it does not process the
+    /// queue nor upload anything. This makes it a cheap way of drawing the
+    /// same text again, e.g. onto a different render target.
+    ///
+    /// The glyphs processed are dropped when the glyph cache is recreated,
+    /// e.g. when it is cleared, since they point to glyphs it no longer
+    /// holds. The queue is then processed again before drawing, which lays
+    /// out and uploads the [retained](#method.retain) sections again, along
+    /// with any sections queued since. Other sections are not drawn until
+    /// they are queued again.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    #[inline]
+    pub fn redraw(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+    ) -> Result<(), String> {
+        self.process_stale(device, staging_belt, encoder)?;
+        self.pipeline
+            .redraw(encoder, target, depth_stencil_attachment, None);
+
+        Ok(())
+    }
+
+    /// Draws the glyphs processed by the last draw call again onto a dirty
+    /// region of a render target, reusing its transform.
+    ///
+    /// Like [`redraw`](#method.redraw-1), it does not process the queue nor
+    /// upload anything, unless the glyph cache was recreated since. Any
+    /// fragments outside of the `dirty` region are scissored. This is useful
+    /// for mostly static interfaces where only a small part of the target
+    /// needs to be updated.
+    ///
+    /// It __does not__ submit the encoder command buffer to the device queue.
+    ///
+    /// # Panics
+    /// Panics if the provided `target` has a texture format that does not match
+    /// the `render_format` provided on creation of the `GlyphBrush`.
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn redraw_region(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        dirty: Region,
+    ) -> Result<(), String> {
+        self.process_stale(device, staging_belt, encoder)?;
+        self.pipeline.redraw(
+            encoder,
+            target,
+            depth_stencil_attachment,
+            Some(dirty),
+        );
+
         Ok(())
     }
 }
 
 /// Helper function to generate a generate a transform matrix.
+#[rustfmt::skip]
 pub fn orthographic_projection(width: u32, height: u32) -> [f32; 16] {
-    #[cfg_attr(rustfmt, rustfmt_skip)]
     [
         2.0 / width as f32, 0.0, 0.0, 0.0,
         0.0, -2.0 / height as f32, 0.0, 0.0,
@@ -465,6 +2563,64 @@ pub fn orthographic_projection(width: u32, height: u32) -> [f32; 16] {
     ]
 }
 
+/// Helper function to generate a transform matrix rotating the drawn text by
+/// `angle` radians around a `pivot`, followed by an
+/// [`orthographic_projection`] of the given size.
+///
+/// The `pivot` is in the same pixel coordinates used to position sections,
+/// where positive angles rotate clockwise.
+pub fn rotate_around(
+    (pivot_x, pivot_y): (f32, f32),
+    angle: f32,
+    width: u32,
+    height: u32,
+) -> [f32; 16] {
+    let (sin, cos) = angle.sin_cos();
+    let (width, height) = (width as f32, height as f32);
+
+    // The translation keeping the pivot in place
+    let x = pivot_x - cos * pivot_x + sin * pivot_y;
+    let y = pivot_y - sin * pivot_x - cos * pivot_y;
+
+    #[rustfmt::skip]
+    let transform = [
+        2.0 * cos / width, -2.0 * sin / height, 0.0, 0.0,
+        -2.0 * sin / width, -2.0 * cos / height, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        2.0 * x / width - 1.0, 1.0 - 2.0 * y / height, 0.0, 1.0,
+    ];
+
+    transform
+}
+
+/// Helper function to convert a point in logical coordinates, e.g. the
+/// coordinates of a window, into the physical pixels used to lay out sections.
+///
+/// The `scale_factor` is the number of physical pixels per logical pixel,
+/// as reported by the windowing library.
+pub fn logical_to_physical(
+    (x, y): (f32, f32),
+    scale_factor: f32,
+) -> (f32, f32) {
+    (x * scale_factor, y * scale_factor)
+}
+
+/// Helper function to create a view of a single layer of a texture array,
+/// which can be used as the render target of a [`GlyphBrush`].
+///
+/// The texture must have the `render_format` provided on creation of the
+/// `GlyphBrush`, like any other target, and the
+/// `wgpu::TextureUsages::RENDER_ATTACHMENT` usage.
+pub fn layer_view(texture: &wgpu::Texture, layer: u32) -> wgpu::TextureView {
+    texture.create_view(&wgpu::TextureViewDescriptor {
+        label: Some("wgpu_glyph::layer_view"),
+        dimension: Some(wgpu::TextureViewDimension::D2),
+        base_array_layer: layer,
+        array_layer_count: std::num::NonZeroU32::new(1),
+        ..Default::default()
+    })
+}
+
 impl<D, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<D, F, H> {
     #[inline]
     fn glyphs_custom_layout<'a, 'b, S, L>(
@@ -476,8 +2632,9 @@ impl<D, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<D, F, H> {
         L: GlyphPositioner + std::hash::Hash,
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush
-            .glyphs_custom_layout(section, custom_layout)
+        self.with_glyph_section(&section.into(), |glyph_brush, section| {
+            glyph_brush.glyphs_custom_layout(section, custom_layout)
+        })
     }
 
     #[inline]
@@ -495,8 +2652,9 @@ impl<D, F: Font, H: BuildHasher> GlyphCruncher<F> for GlyphBrush<D, F, H> {
         L: GlyphPositioner + std::hash::Hash,
         S: Into<Cow<'a, Section<'a>>>,
     {
-        self.glyph_brush
-            .glyph_bounds_custom_layout(section, custom_layout)
+        self.with_glyph_section(&section.into(), |glyph_brush, section| {
+            glyph_brush.glyph_bounds_custom_layout(section, custom_layout)
+        })
     }
 }
 
@@ -506,3 +2664,74 @@ impl<F, H> std::fmt::Debug for GlyphBrush<F, H> {
         write!(f, "GlyphBrush")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the pixel coordinates of the given point after applying the
+    /// transform, in a target of the given size.
+    fn apply(
+        transform: [f32; 16],
+        (x, y): (f32, f32),
+        (width, height): (f32, f32),
+    ) -> (f32, f32) {
+        let ndc_x = transform[0] * x + transform[4] * y + transform[12];
+        let ndc_y = transform[1] * x + transform[5] * y + transform[13];
+
+        ((ndc_x + 1.0) * width / 2.0, (1.0 - ndc_y) * height / 2.0)
+    }
+
+    fn assert_near((x, y): (f32, f32), (expected_x, expected_y): (f32, f32)) {
+        assert!(
+            (x - expected_x).abs() < 1e-3 && (y - expected_y).abs() < 1e-3,
+            "({}, {}) is not ({}, {})",
+            x,
+            y,
+            expected_x,
+            expected_y
+        );
+    }
+
+    #[test]
+    fn rotate_around_keeps_the_pivot_in_place() {
+        let transform = rotate_around((30.0, 40.0), 1.0, 200, 100);
+
+        assert_near(
+            apply(transform, (30.0, 40.0), (200.0, 100.0)),
+            (30.0, 40.0),
+        );
+    }
+
+    #[test]
+    fn rotate_around_rotates_bounds_clockwise() {
+        let transform =
+            rotate_around((50.0, 50.0), std::f32::consts::FRAC_PI_2, 100, 100);
+
+        let corners: Vec<_> =
+            [(60.0, 40.0), (80.0, 40.0), (60.0, 50.0), (80.0, 50.0)]
+                .iter()
+                .map(|&corner| apply(transform, corner, (100.0, 100.0)))
+                .collect();
+
+        let min_x = corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min);
+        let max_x = corners.iter().map(|c| c.0).fold(f32::MIN, f32::max);
+        let min_y = corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min);
+        let max_y = corners.iter().map(|c| c.1).fold(f32::MIN, f32::max);
+
+        assert_near((min_x, min_y), (50.0, 60.0));
+        assert_near((max_x, max_y), (60.0, 80.0));
+    }
+
+    #[test]
+    fn rotate_around_without_angle_is_orthographic() {
+        assert_eq!(
+            apply(
+                rotate_around((10.0, 10.0), 0.0, 64, 32),
+                (5.0, 7.0),
+                (64.0, 32.0)
+            ),
+            apply(orthographic_projection(64, 32), (5.0, 7.0), (64.0, 32.0))
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use glyph_brush::ab_glyph::{Font, ScaleFont};
+use glyph_brush::SectionGlyph;
+
+/// The vertical metrics of a line of a section, in the same pixel coordinates
+/// used to position sections.
+///
+/// Use [`GlyphBrush::lines`](struct.GlyphBrush.html#method.lines) to obtain
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineMetrics {
+    /// The vertical position of the baseline of the line.
+    pub baseline: f32,
+    /// The highest ascent of the glyphs of the line, above the baseline.
+    pub ascent: f32,
+    /// The lowest descent of the glyphs of the line, usually negative.
+    pub descent: f32,
+    /// The biggest line gap of the glyphs of the line.
+    pub line_gap: f32,
+}
+
+impl LineMetrics {
+    /// Returns the vertical position of the top of the line.
+    pub fn top(&self) -> f32 {
+        self.baseline - self.ascent
+    }
+
+    /// Returns the height of the line, from its ascent to its descent.
+    pub fn height(&self) -> f32 {
+        self.ascent - self.descent
+    }
+}
+
+/// Groups laid out glyphs into lines, telling them apart by their baseline.
+///
+/// Lines without any glyph, like the ones produced by consecutive line
+/// breaks, have no metrics of their own.
+pub fn lines<'a, F: Font>(
+    fonts: &[F],
+    glyphs: impl Iterator<Item = &'a SectionGlyph>,
+) -> Vec<LineMetrics> {
+    let mut lines: Vec<LineMetrics> = Vec::new();
+
+    for glyph in glyphs {
+        let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+        let baseline = glyph.glyph.position.y;
+
+        match lines.last_mut() {
+            Some(line) if line.baseline == baseline => {
+                line.ascent = line.ascent.max(font.ascent());
+                line.descent = line.descent.min(font.descent());
+                line.line_gap = line.line_gap.max(font.line_gap());
+            }
+            _ => lines.push(LineMetrics {
+                baseline,
+                ascent: font.ascent(),
+                descent: font.descent(),
+                line_gap: font.line_gap(),
+            }),
+        }
+    }
+
+    lines
+}
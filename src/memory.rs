@@ -0,0 +1,24 @@
+/// The GPU memory held by a [`GlyphBrush`](struct.GlyphBrush.html), in bytes.
+///
+/// Obtain it with
+/// [`GlyphBrush::memory_usage`](struct.GlyphBrush.html#method.memory_usage).
+/// Resources owned by you, like staging belts or fill textures, are not
+/// included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryReport {
+    /// The glyph cache texture.
+    pub cache: u64,
+    /// The buffer used to upload glyphs into the glyph cache.
+    pub cache_upload: u64,
+    /// The buffers holding the instances to draw.
+    pub instances: u64,
+    /// The uniform and index buffers.
+    pub uniforms: u64,
+}
+
+impl MemoryReport {
+    /// Returns the total amount of bytes held.
+    pub fn total(&self) -> u64 {
+        self.cache + self.cache_upload + self.instances + self.uniforms
+    }
+}
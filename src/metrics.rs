@@ -0,0 +1,21 @@
+/// The vertical metrics of a font at a given scale, in pixels.
+///
+/// Use [`GlyphBrush::v_metrics`](struct.GlyphBrush.html#method.v_metrics) to
+/// obtain them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VMetrics {
+    /// The height of the font above the baseline.
+    pub ascent: f32,
+    /// The depth of the font below the baseline, usually negative.
+    pub descent: f32,
+    /// The extra space the font recommends between consecutive lines.
+    pub line_gap: f32,
+}
+
+impl VMetrics {
+    /// Returns the distance between the baselines of consecutive lines, as
+    /// used when laying out sections.
+    pub fn line_height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+}
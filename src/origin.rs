@@ -0,0 +1,77 @@
+use glyph_brush::ab_glyph::{point, Rect};
+
+/// The corner of the render target where the coordinates used to position
+/// sections start.
+///
+/// Text is always laid out downwards, from one line to the next, and drawn
+/// upright. With a bottom-left origin, the y coordinates of sections are
+/// negated when they are queued, so measurements of the laid out text (e.g.
+/// [`glyph_bounds`](trait.GlyphCruncher.html#method.glyph_bounds) or
+/// [`lines`](struct.GlyphBrush.html#method.lines)) and instances drawn
+/// directly use negated y coordinates as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// The top-left corner, with the y axis pointing down, as in most UI
+    /// toolkits.
+    TopLeft,
+    /// The bottom-left corner, with the y axis pointing up, as in OpenGL.
+    ///
+    /// Transforms given to the `GlyphBrush` have to map this space as well,
+    /// e.g. with a projection mapping `0.0` to the bottom of the target.
+    BottomLeft,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Origin {
+    fn default() -> Self {
+        Origin::TopLeft
+    }
+}
+
+impl Origin {
+    /// Converts a position from this space to the space text is laid out in.
+    pub(crate) fn position(self, (x, y): (f32, f32)) -> (f32, f32) {
+        match self {
+            Origin::TopLeft => (x, y),
+            Origin::BottomLeft => (x, -y),
+        }
+    }
+
+    /// Converts a rectangle from this space to the space text is laid out in.
+    pub(crate) fn rect(self, rect: Rect) -> Rect {
+        match self {
+            Origin::TopLeft => rect,
+            Origin::BottomLeft => Rect {
+                min: point(rect.min.x, -rect.max.y),
+                max: point(rect.max.x, -rect.min.y),
+            },
+        }
+    }
+
+    /// Returns an orthographic projection of a target of the given size,
+    /// mapping this space.
+    #[rustfmt::skip]
+    pub(crate) fn projection(self, width: u32, height: u32) -> [f32; 16] {
+        match self {
+            Origin::TopLeft => crate::orthographic_projection(width, height),
+            Origin::BottomLeft => [
+                2.0 / width as f32, 0.0, 0.0, 0.0,
+                0.0, 2.0 / height as f32, 0.0, 0.0,
+                0.0, 0.0, 1.0, 0.0,
+                -1.0, -1.0, 0.0, 1.0,
+            ],
+        }
+    }
+
+    /// Makes the given column-major transform of this space apply to the
+    /// space text is laid out in.
+    pub(crate) fn transform(self, mut transform: [f32; 16]) -> [f32; 16] {
+        if self == Origin::BottomLeft {
+            for row in 0..4 {
+                transform[4 + row] = -transform[4 + row];
+            }
+        }
+
+        transform
+    }
+}
@@ -0,0 +1,40 @@
+/// A path made of straight segments, along which text can be laid out.
+pub struct Polyline<'a> {
+    points: &'a [(f32, f32)],
+}
+
+impl<'a> Polyline<'a> {
+    pub fn new(points: &'a [(f32, f32)]) -> Polyline<'a> {
+        Polyline { points }
+    }
+
+    /// Returns the point at the given arc length along the path and the angle
+    /// of the path there, in radians, or `None` if it is out of the path.
+    pub fn at(&self, distance: f32) -> Option<((f32, f32), f32)> {
+        if distance < 0.0 {
+            return None;
+        }
+
+        let mut travelled = 0.0;
+
+        for segment in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+            let (dx, dy) = (x1 - x0, y1 - y0);
+            let length = (dx * dx + dy * dy).sqrt();
+
+            if length == 0.0 {
+                continue;
+            }
+
+            if distance <= travelled + length {
+                let t = (distance - travelled) / length;
+
+                return Some(((x0 + dx * t, y0 + dy * t), dy.atan2(dx)));
+            }
+
+            travelled += length;
+        }
+
+        None
+    }
+}
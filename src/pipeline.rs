@@ -1,11 +1,20 @@
+mod buffer;
 mod cache;
 
-use crate::Region;
+use crate::builder::Settings;
+use crate::clip::RoundedClip;
+use crate::effect::Effect;
+use crate::extra::GlyphExtra;
+use crate::gradient::Span;
+use crate::memory::MemoryReport;
+use crate::{Region, Viewport};
+use buffer::InstanceBuffer;
 use cache::Cache;
 
 use bytemuck::{Pod, Zeroable};
 use core::num::NonZeroU64;
 use glyph_brush::ab_glyph::{point, Rect};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::mem;
 
@@ -13,29 +22,51 @@ pub struct Pipeline<Depth> {
     transform: wgpu::Buffer,
     sampler: wgpu::Sampler,
     cache: Cache,
+    cache_generation: u64,
+    fill_sampler: wgpu::Sampler,
+    fill: Option<wgpu::TextureView>,
+    current_fragment: [f32; 4],
     uniform_layout: wgpu::BindGroupLayout,
     uniforms: wgpu::BindGroup,
+    layout: wgpu::PipelineLayout,
+    shader: wgpu::ShaderModule,
     raw: wgpu::RenderPipeline,
-    instances: wgpu::Buffer,
-    current_instances: usize,
-    supported_instances: usize,
-    current_transform: [f32; 16],
+    other_formats: HashMap<wgpu::TextureFormat, wgpu::RenderPipeline>,
+    indices: Option<wgpu::Buffer>,
+    instances: InstanceBuffer,
+    custom_instances: Option<InstanceBuffer>,
+    custom_uploaded: Vec<Instance>,
+    changed: bool,
+    current_transform: Option<[f32; 16]>,
+    clip: Option<RoundedClip>,
+    clip_bounds: Option<Region>,
+    viewport: Option<Viewport>,
+    current_clip: Option<RoundedClip>,
+    effect: Option<Effect>,
+    time: f32,
+    fade_progress: Option<f32>,
+    current_effect: [f32; 8],
+    render_format: wgpu::TextureFormat,
+    depth_stencil: Option<wgpu::DepthStencilState>,
+    settings: Settings,
     depth: PhantomData<Depth>,
 }
 
 impl Pipeline<()> {
     pub fn new(
         device: &wgpu::Device,
-        filter_mode: wgpu::FilterMode,
         render_format: wgpu::TextureFormat,
+        settings: &Settings,
+        uniform_layout: Option<wgpu::BindGroupLayout>,
         cache_width: u32,
         cache_height: u32,
     ) -> Pipeline<()> {
         build(
             device,
-            filter_mode,
             render_format,
             None,
+            settings,
+            uniform_layout,
             cache_width,
             cache_height,
         )
@@ -61,27 +92,93 @@ impl Pipeline<()> {
             region,
         );
     }
+
+    pub fn draw_instances(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        transform: [f32; 16],
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        draw_custom(
+            self,
+            device,
+            staging_belt,
+            encoder,
+            target,
+            None,
+            transform,
+            instances,
+            None,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_sprites(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        transform: [f32; 16],
+        bind_group: &wgpu::BindGroup,
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        draw_custom(
+            self,
+            device,
+            staging_belt,
+            encoder,
+            target,
+            None,
+            transform,
+            instances,
+            Some(bind_group),
+        )
+    }
+
+    pub fn redraw(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        region: Option<Region>,
+    ) {
+        redraw(
+            self,
+            &self.instances,
+            &self.uniforms,
+            encoder,
+            target,
+            None,
+            region,
+        );
+    }
 }
 
 impl Pipeline<wgpu::DepthStencilState> {
     pub fn new(
         device: &wgpu::Device,
-        filter_mode: wgpu::FilterMode,
         render_format: wgpu::TextureFormat,
         depth_stencil_state: wgpu::DepthStencilState,
+        settings: &Settings,
+        uniform_layout: Option<wgpu::BindGroupLayout>,
         cache_width: u32,
         cache_height: u32,
     ) -> Pipeline<wgpu::DepthStencilState> {
         build(
             device,
-            filter_mode,
             render_format,
             Some(depth_stencil_state),
+            settings,
+            uniform_layout,
             cache_width,
             cache_height,
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn draw(
         &mut self,
         device: &wgpu::Device,
@@ -103,84 +200,514 @@ impl Pipeline<wgpu::DepthStencilState> {
             region,
         );
     }
-}
 
-impl<Depth> Pipeline<Depth> {
-    pub fn update_cache(
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_instances(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        transform: [f32; 16],
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        draw_custom(
+            self,
+            device,
+            staging_belt,
+            encoder,
+            target,
+            Some(depth_stencil_attachment),
+            transform,
+            instances,
+            None,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_sprites(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        transform: [f32; 16],
+        bind_group: &wgpu::BindGroup,
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        draw_custom(
+            self,
+            device,
+            staging_belt,
+            encoder,
+            target,
+            Some(depth_stencil_attachment),
+            transform,
+            instances,
+            Some(bind_group),
+        )
+    }
+
+    pub fn redraw(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment,
+        region: Option<Region>,
+    ) {
+        redraw(
+            self,
+            &self.instances,
+            &self.uniforms,
+            encoder,
+            target,
+            Some(depth_stencil_attachment),
+            region,
+        );
+    }
+}
+
+impl<Depth> Pipeline<Depth> {
+    pub fn update_cache(
+        &mut self,
         offset: [u16; 2],
         size: [u16; 2],
         data: &[u8],
     ) {
-        self.cache
-            .update(device, staging_belt, encoder, offset, size, data);
+        self.cache.update(offset, size, data);
+    }
+
+    /// Uploads the glyphs waiting to be uploaded to the cache, up to the
+    /// upload budget left after uploading `uploaded` glyphs in the same draw.
+    pub fn flush_cache(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        uploaded: usize,
+    ) -> usize {
+        let budget = self
+            .settings
+            .upload_budget
+            .map(|budget| budget.saturating_sub(uploaded));
+
+        self.cache.flush(device, staging_belt, encoder, budget)
+    }
+
+    /// Returns whether any glyph is waiting to be uploaded to the cache,
+    /// because of the upload budget.
+    pub fn has_pending_uploads(&self) -> bool {
+        self.cache.has_pending()
+    }
+
+    /// Returns whether the glyph of the given instance, if any, is uploaded
+    /// to the cache.
+    pub fn is_uploaded(&self, instance: &Instance) -> bool {
+        let [left, bottom] = instance.tex_left_top;
+        let [right, top] = instance.tex_right_bottom;
+
+        // The texture coordinates of mirrored glyphs are swapped
+        instance.tex_left_top[0] < 0.0
+            || !self.cache.is_pending(
+                [left.min(right), top.min(bottom)],
+                [left.max(right), top.max(bottom)],
+            )
     }
 
-    pub fn increase_cache_size(
+    pub fn resize_cache(
         &mut self,
         device: &wgpu::Device,
         width: u32,
         height: u32,
     ) {
-        self.cache = Cache::new(device, width, height);
+        self.cache = Cache::new(device, self.cache.format(), width, height);
+        self.uniforms = self.create_bind_group(device, &self.cache.view);
+
+        // Uploaded instances point to the old cache
+        self.instances.clear();
+        self.cache_generation += 1;
+        self.changed = true;
+    }
+
+    pub fn set_texture_filter_method(
+        &mut self,
+        device: &wgpu::Device,
+        filter_method: wgpu::FilterMode,
+    ) {
+        self.sampler = create_sampler(device, filter_method);
+        self.settings.texture_filter_method = filter_method;
+        self.uniforms = self.create_bind_group(device, &self.cache.view);
+    }
+
+    pub fn set_render_format(
+        &mut self,
+        device: &wgpu::Device,
+        render_format: wgpu::TextureFormat,
+    ) {
+        self.raw = create_render_pipeline(
+            device,
+            &self.layout,
+            &self.shader,
+            render_format,
+            self.depth_stencil.clone(),
+            &self.settings,
+        );
+        self.render_format = render_format;
+    }
+
+    pub fn prepare_render_format(
+        &mut self,
+        device: &wgpu::Device,
+        render_format: wgpu::TextureFormat,
+    ) {
+        if render_format == self.render_format
+            || self.other_formats.contains_key(&render_format)
+        {
+            return;
+        }
+
+        let raw = create_render_pipeline(
+            device,
+            &self.layout,
+            &self.shader,
+            render_format,
+            self.depth_stencil.clone(),
+            &self.settings,
+        );
+
+        let _ = self.other_formats.insert(render_format, raw);
+    }
+
+    pub fn set_fill_texture(
+        &mut self,
+        device: &wgpu::Device,
+        texture: Option<wgpu::TextureView>,
+    ) {
+        self.fill = texture;
+        self.uniforms = self.create_bind_group(device, &self.cache.view);
+    }
+
+    pub fn recreate(
+        &mut self,
+        device: &wgpu::Device,
+        cache_width: u32,
+        cache_height: u32,
+    ) {
+        let clip = self.clip;
+        let clip_bounds = self.clip_bounds;
+        let viewport = self.viewport;
+        let cache_generation = self.cache_generation;
+        let effect = self.effect;
+        let time = self.time;
+        let fade_progress = self.fade_progress;
 
-        self.uniforms = create_uniforms(
+        *self = build(
+            device,
+            self.render_format,
+            self.depth_stencil.clone(),
+            &self.settings,
+            None,
+            cache_width,
+            cache_height,
+        );
+
+        self.clip = clip;
+        self.clip_bounds = clip_bounds;
+        self.viewport = viewport;
+        self.cache_generation = cache_generation + 1;
+        self.changed = true;
+        self.effect = effect;
+        self.time = time;
+        self.fade_progress = fade_progress;
+    }
+
+    pub fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        texture: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        create_uniforms(
             device,
             &self.uniform_layout,
             &self.transform,
             &self.sampler,
-            &self.cache.view,
-        );
+            texture,
+            &self.fill_sampler,
+            self.fill.as_ref().unwrap_or(&self.cache.view),
+        )
     }
 
-    pub fn upload(
+    pub fn load_op(&self) -> wgpu::LoadOp<wgpu::Color> {
+        self.settings.load_op
+    }
+
+    pub fn set_load_op(&mut self, load_op: wgpu::LoadOp<wgpu::Color>) {
+        self.settings.load_op = load_op;
+    }
+
+    pub fn set_clip(&mut self, clip: Option<RoundedClip>) {
+        self.clip = clip;
+    }
+
+    pub fn set_clip_bounds(&mut self, clip_bounds: Option<Region>) {
+        self.clip_bounds = clip_bounds;
+    }
+
+    pub fn set_viewport(&mut self, viewport: Option<Viewport>) {
+        self.viewport = viewport;
+    }
+
+    pub fn set_effect(&mut self, effect: Option<Effect>) {
+        self.effect = effect;
+    }
+
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    pub fn set_fade_progress(&mut self, progress: Option<f32>) {
+        self.fade_progress = progress;
+    }
+
+    pub fn transform(&self) -> [f32; 16] {
+        self.current_transform.unwrap_or(IDENTITY_MATRIX)
+    }
+
+    pub fn sampler(&self) -> &wgpu::Sampler {
+        &self.sampler
+    }
+
+    pub fn uniform_buffer(&self) -> &wgpu::Buffer {
+        &self.transform
+    }
+
+    pub fn render_format(&self) -> wgpu::TextureFormat {
+        self.render_format
+    }
+
+    pub fn update_transform(
         &mut self,
         device: &wgpu::Device,
         staging_belt: &mut wgpu::util::StagingBelt,
         encoder: &mut wgpu::CommandEncoder,
-        instances: &[Instance],
+        transform: [f32; 16],
     ) {
-        if instances.is_empty() {
-            self.current_instances = 0;
-            return;
+        if Some(transform) != self.current_transform {
+            let mut transform_view = staging_belt.write_buffer(
+                encoder,
+                &self.transform,
+                0,
+                unsafe { NonZeroU64::new_unchecked(16 * 4) },
+                device,
+            );
+
+            let uploaded = self.settings.origin.transform(transform);
+            let uploaded = if self.settings.flip_y {
+                flip_y(uploaded)
+            } else {
+                uploaded
+            };
+
+            transform_view.copy_from_slice(bytemuck::cast_slice(&uploaded));
+
+            self.current_transform = Some(transform);
+            self.changed = true;
         }
 
-        if instances.len() > self.supported_instances {
-            self.instances = device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("wgpu_glyph::Pipeline instances"),
-                size: mem::size_of::<Instance>() as u64
-                    * instances.len() as u64,
-                usage: wgpu::BufferUsages::VERTEX
-                    | wgpu::BufferUsages::COPY_DST,
-                mapped_at_creation: false,
-            });
-
-            self.supported_instances = instances.len();
+        if self.clip != self.current_clip {
+            let mut clip_view = staging_belt.write_buffer(
+                encoder,
+                &self.transform,
+                16 * 4,
+                unsafe { NonZeroU64::new_unchecked(8 * 4) },
+                device,
+            );
+
+            clip_view.copy_from_slice(bytemuck::cast_slice(
+                &RoundedClip::uniforms(self.clip),
+            ));
+
+            self.current_clip = self.clip;
+            self.changed = true;
         }
 
-        let instances_bytes = bytemuck::cast_slice(instances);
+        let effect =
+            Effect::uniforms(self.effect, self.time, self.fade_progress);
 
-        if let Some(size) = NonZeroU64::new(instances_bytes.len() as u64) {
-            let mut instances_view = staging_belt.write_buffer(
+        if effect != self.current_effect {
+            let mut effect_view = staging_belt.write_buffer(
                 encoder,
-                &self.instances,
-                0,
-                size,
+                &self.transform,
+                24 * 4,
+                unsafe { NonZeroU64::new_unchecked(8 * 4) },
+                device,
+            );
+
+            effect_view.copy_from_slice(bytemuck::cast_slice(&effect));
+
+            self.current_effect = effect;
+            self.changed = true;
+        }
+
+        let fragment = [
+            if self.fill.is_some() { 1.0 } else { 0.0 },
+            self.settings.alpha_cutoff.unwrap_or(0.0),
+            self.settings.gamma,
+            self.settings.edge_softness,
+        ];
+
+        if fragment != self.current_fragment {
+            let mut fragment_view = staging_belt.write_buffer(
+                encoder,
+                &self.transform,
+                32 * 4,
+                unsafe { NonZeroU64::new_unchecked(4 * 4) },
                 device,
             );
 
-            instances_view.copy_from_slice(instances_bytes);
+            fragment_view.copy_from_slice(bytemuck::cast_slice(&fragment));
+
+            self.current_fragment = fragment;
+            self.changed = true;
+        }
+    }
+
+    pub fn draw_into_pass<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.draw_instances_into_pass(
+            &self.instances,
+            &self.uniforms,
+            render_pass,
+        );
+    }
+
+    pub fn draw_into_pass_with_format<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        render_format: wgpu::TextureFormat,
+    ) -> Result<(), String> {
+        if render_format == self.render_format {
+            self.draw_into_pass(render_pass);
+
+            return Ok(());
         }
 
-        self.current_instances = instances.len();
+        let raw = self.other_formats.get(&render_format).ok_or_else(|| {
+            format!(
+                "No render pipeline was prepared for the format {:?}",
+                render_format
+            )
+        })?;
+
+        render_pass.set_pipeline(raw);
+        render_pass.set_bind_group(0, &self.uniforms, &[]);
+
+        self.instances
+            .draw_into_pass(render_pass, self.indices.as_ref());
+
+        Ok(())
+    }
+
+    fn draw_instances_into_pass<'a>(
+        &'a self,
+        instances: &'a InstanceBuffer,
+        bind_group: &'a wgpu::BindGroup,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        render_pass.set_pipeline(&self.raw);
+        render_pass.set_bind_group(0, bind_group, &[]);
+
+        instances.draw_into_pass(render_pass, self.indices.as_ref());
+    }
+
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        self.instances
+            .upload(device, staging_belt, encoder, instances)?;
+        self.changed = true;
+
+        Ok(())
+    }
+
+    pub fn memory_usage(&self) -> MemoryReport {
+        let indices = if self.indices.is_some() {
+            mem::size_of_val(&QUAD_INDICES) as u64
+        } else {
+            0
+        };
+
+        MemoryReport {
+            cache: self.cache.texture_bytes(),
+            cache_upload: self.cache.upload_bytes(),
+            instances: self.instances.bytes()
+                + self
+                    .custom_instances
+                    .as_ref()
+                    .map_or(0, InstanceBuffer::bytes),
+            uniforms: mem::size_of_val(&initial_uniforms()) as u64 + indices,
+        }
+    }
+
+    pub fn reset_changes(&mut self) {
+        self.changed = false;
+    }
+
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    pub fn cache_generation(&self) -> u64 {
+        self.cache_generation
+    }
+
+    fn upload_custom(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        let skip = self.settings.skip_identical_instances;
+
+        if skip
+            && self.custom_instances.is_some()
+            && self.custom_uploaded == instances
+        {
+            return Ok(());
+        }
+
+        let max_instances = self.settings.max_instances;
+
+        self.custom_instances
+            .get_or_insert_with(|| {
+                InstanceBuffer::new(device, instances.len(), max_instances)
+            })
+            .upload(device, staging_belt, encoder, instances)?;
+
+        if skip {
+            self.custom_uploaded.clear();
+            self.custom_uploaded.extend_from_slice(instances);
+        }
+
+        Ok(())
     }
 }
 
 // Helpers
-#[cfg_attr(rustfmt, rustfmt_skip)]
+/// The indices of the 2 triangles of a quad, matching the vertices of the
+/// triangle strip built by the vertex shader.
+const QUAD_INDICES: [u16; 6] = [0, 1, 2, 2, 1, 3];
+
+#[rustfmt::skip]
 const IDENTITY_MATRIX: [f32; 16] = [
     1.0, 0.0, 0.0, 0.0,
     0.0, 1.0, 0.0, 0.0,
@@ -188,11 +715,22 @@ const IDENTITY_MATRIX: [f32; 16] = [
     0.0, 0.0, 0.0, 1.0,
 ];
 
+/// Negates the Y axis of the clip space coordinates produced by the given
+/// column-major transform.
+fn flip_y(mut transform: [f32; 16]) -> [f32; 16] {
+    for column in 0..4 {
+        transform[column * 4 + 1] = -transform[column * 4 + 1];
+    }
+
+    transform
+}
+
 fn build<D>(
     device: &wgpu::Device,
-    filter_mode: wgpu::FilterMode,
     render_format: wgpu::TextureFormat,
     depth_stencil: Option<wgpu::DepthStencilState>,
+    settings: &Settings,
+    uniform_layout: Option<wgpu::BindGroupLayout>,
     cache_width: u32,
     cache_height: u32,
 ) -> Pipeline<D> {
@@ -201,77 +739,48 @@ fn build<D>(
     let transform =
         device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: None,
-            contents: bytemuck::cast_slice(&IDENTITY_MATRIX),
+            contents: bytemuck::cast_slice(&initial_uniforms()),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
-    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-        address_mode_u: wgpu::AddressMode::ClampToEdge,
-        address_mode_v: wgpu::AddressMode::ClampToEdge,
-        address_mode_w: wgpu::AddressMode::ClampToEdge,
-        mag_filter: filter_mode,
-        min_filter: filter_mode,
-        mipmap_filter: filter_mode,
-        ..Default::default()
-    });
+    let sampler = create_sampler(device, settings.texture_filter_method);
 
-    let cache = Cache::new(device, cache_width, cache_height);
+    let cache =
+        Cache::new(device, settings.cache_format, cache_width, cache_height);
 
-    let uniform_layout =
+    let uniform_layout = uniform_layout.unwrap_or_else(|| {
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("wgpu_glyph::Pipeline uniforms"),
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: wgpu::BufferSize::new(
-                            mem::size_of::<[f32; 16]>() as u64,
-                        ),
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Sampler {
-                        filtering: true,
-                        comparison: false,
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: wgpu::ShaderStages::FRAGMENT,
-                    ty: wgpu::BindingType::Texture {
-                        sample_type: wgpu::TextureSampleType::Float {
-                            filterable: false,
-                        },
-                        view_dimension: wgpu::TextureViewDimension::D2,
-                        multisampled: false,
-                    },
-                    count: None,
-                },
-            ],
-        });
+            entries: &uniform_layout_entries(),
+        })
+    });
+
+    let fill_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("wgpu_glyph::Pipeline fill"),
+        address_mode_u: wgpu::AddressMode::Repeat,
+        address_mode_v: wgpu::AddressMode::Repeat,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
 
+    // Without a fill texture, the glyph cache is bound in its place and the
+    // shader ignores it
     let uniforms = create_uniforms(
         device,
         &uniform_layout,
         &transform,
         &sampler,
         &cache.view,
+        &fill_sampler,
+        &cache.view,
     );
 
-    let instances = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("wgpu_glyph::Pipeline instances"),
-        size: mem::size_of::<Instance>() as u64
-            * Instance::INITIAL_AMOUNT as u64,
-        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-        mapped_at_creation: false,
-    });
+    let instances = InstanceBuffer::new(
+        device,
+        settings.instance_capacity,
+        settings.max_instances,
+    );
 
     let layout =
         device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -282,17 +791,87 @@ fn build<D>(
 
     let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
         label: Some("Glyph Shader"),
-        source: wgpu::ShaderSource::Wgsl(crate::Cow::Borrowed(include_str!(
-            "shader/glyph.wgsl"
-        ))),
+        source: wgpu::ShaderSource::Wgsl(crate::Cow::Borrowed(
+            settings
+                .shader
+                .map_or(include_str!("shader/glyph.wgsl"), |shader| {
+                    shader.source
+                }),
+        )),
     });
 
-    let raw = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+    let indices = match settings.topology {
+        Topology::TriangleStrip => None,
+        Topology::TriangleList => Some(device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("wgpu_glyph::Pipeline quad indices"),
+                contents: bytemuck::cast_slice(&QUAD_INDICES),
+                usage: wgpu::BufferUsages::INDEX,
+            },
+        )),
+    };
+
+    let raw = create_render_pipeline(
+        device,
+        &layout,
+        &shader,
+        render_format,
+        depth_stencil.clone(),
+        settings,
+    );
+
+    Pipeline {
+        transform,
+        sampler,
+        cache,
+        cache_generation: 0,
+        fill_sampler,
+        fill: None,
+        current_fragment: [0.0; 4],
+        uniform_layout,
+        uniforms,
+        layout,
+        shader,
+        raw,
+        other_formats: HashMap::new(),
+        indices,
+        instances,
+        custom_instances: None,
+        custom_uploaded: Vec::new(),
+        changed: false,
+        current_transform: None,
+        clip: None,
+        clip_bounds: None,
+        viewport: None,
+        current_clip: None,
+        effect: None,
+        time: 0.0,
+        fade_progress: None,
+        current_effect: Effect::uniforms(None, 0.0, None),
+        render_format,
+        depth_stencil,
+        settings: *settings,
+        depth: PhantomData,
+    }
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    render_format: wgpu::TextureFormat,
+    depth_stencil: Option<wgpu::DepthStencilState>,
+    settings: &Settings,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
         label: None,
-        layout: Some(&layout),
+        layout: Some(layout),
         vertex: wgpu::VertexState {
-            module: &shader,
-            entry_point: "vs_main",
+            module: shader,
+            entry_point: settings.shader.map_or_else(
+                || settings.color_space.vertex_entry_point(render_format),
+                |shader| shader.vertex_entry_point,
+            ),
             buffers: &[wgpu::VertexBufferLayout {
                 array_stride: mem::size_of::<Instance>() as u64,
                 step_mode: wgpu::VertexStepMode::Instance,
@@ -302,24 +881,45 @@ fn build<D>(
                     2 => Float32x2,
                     3 => Float32x2,
                     4 => Float32x4,
+                    5 => Float32x4,
+                    6 => Float32x2,
+                    7 => Float32x3,
+                    8 => Float32x4,
+                    9 => Uint32,
                 ],
             }],
         },
         primitive: wgpu::PrimitiveState {
-            topology: wgpu::PrimitiveTopology::TriangleStrip,
-            front_face: wgpu::FrontFace::Cw,
+            topology: match settings.topology {
+                Topology::TriangleStrip => {
+                    wgpu::PrimitiveTopology::TriangleStrip
+                }
+                Topology::TriangleList => wgpu::PrimitiveTopology::TriangleList,
+            },
+            front_face: settings.front_face,
+            cull_mode: settings.cull_mode,
             ..Default::default()
         },
         depth_stencil,
         multisample: wgpu::MultisampleState::default(),
         fragment: Some(wgpu::FragmentState {
-            module: &shader,
-            entry_point: "fs_main",
+            module: shader,
+            entry_point: match settings.shader {
+                Some(shader) => shader.fragment_entry_point,
+                None if settings.premultiplied_output => {
+                    "fs_main_premultiplied"
+                }
+                None => "fs_main",
+            },
             targets: &[wgpu::ColorTargetState {
                 format: render_format,
                 blend: Some(wgpu::BlendState {
                     color: wgpu::BlendComponent {
-                        src_factor: wgpu::BlendFactor::SrcAlpha,
+                        src_factor: if settings.premultiplied_output {
+                            wgpu::BlendFactor::One
+                        } else {
+                            wgpu::BlendFactor::SrcAlpha
+                        },
                         dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                         operation: wgpu::BlendOperation::Add,
                     },
@@ -332,23 +932,10 @@ fn build<D>(
                 write_mask: wgpu::ColorWrites::ALL,
             }],
         }),
-    });
-
-    Pipeline {
-        transform,
-        sampler,
-        cache,
-        uniform_layout,
-        uniforms,
-        raw,
-        instances,
-        current_instances: 0,
-        supported_instances: Instance::INITIAL_AMOUNT,
-        current_transform: [0.0; 16],
-        depth: PhantomData,
-    }
+    })
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw<D>(
     pipeline: &mut Pipeline<D>,
     device: &wgpu::Device,
@@ -359,20 +946,61 @@ fn draw<D>(
     transform: [f32; 16],
     region: Option<Region>,
 ) {
-    if transform != pipeline.current_transform {
-        let mut transform_view = staging_belt.write_buffer(
-            encoder,
-            &pipeline.transform,
-            0,
-            unsafe { NonZeroU64::new_unchecked(16 * 4) },
-            device,
-        );
+    pipeline.update_transform(device, staging_belt, encoder, transform);
 
-        transform_view.copy_from_slice(bytemuck::cast_slice(&transform));
+    redraw(
+        pipeline,
+        &pipeline.instances,
+        &pipeline.uniforms,
+        encoder,
+        target,
+        depth_stencil_attachment,
+        region,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_custom<D>(
+    pipeline: &mut Pipeline<D>,
+    device: &wgpu::Device,
+    staging_belt: &mut wgpu::util::StagingBelt,
+    encoder: &mut wgpu::CommandEncoder,
+    target: &wgpu::TextureView,
+    depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment>,
+    transform: [f32; 16],
+    instances: &[Instance],
+    bind_group: Option<&wgpu::BindGroup>,
+) -> Result<(), String> {
+    // The changes are only tracked for the queued instances, which are drawn
+    // with the same uniforms, so only changing those counts
+    pipeline.update_transform(device, staging_belt, encoder, transform);
+    pipeline.upload_custom(device, staging_belt, encoder, instances)?;
 
-        pipeline.current_transform = transform;
+    if let Some(custom_instances) = &pipeline.custom_instances {
+        redraw(
+            pipeline,
+            custom_instances,
+            bind_group.unwrap_or(&pipeline.uniforms),
+            encoder,
+            target,
+            depth_stencil_attachment,
+            None,
+        );
     }
 
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn redraw<D>(
+    pipeline: &Pipeline<D>,
+    instances: &InstanceBuffer,
+    bind_group: &wgpu::BindGroup,
+    encoder: &mut wgpu::CommandEncoder,
+    target: &wgpu::TextureView,
+    depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment>,
+    region: Option<Region>,
+) {
     let mut render_pass =
         encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("wgpu_glyph::pipeline render pass"),
@@ -380,16 +1008,30 @@ fn draw<D>(
                 view: target,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
+                    load: pipeline.settings.load_op,
                     store: true,
                 },
             }],
             depth_stencil_attachment,
         });
 
-    render_pass.set_pipeline(&pipeline.raw);
-    render_pass.set_bind_group(0, &pipeline.uniforms, &[]);
-    render_pass.set_vertex_buffer(0, pipeline.instances.slice(..));
+    let region = match (pipeline.clip_bounds, region) {
+        (Some(clip_bounds), Some(region)) => {
+            Some(clip_bounds.intersection(region))
+        }
+        (clip_bounds, region) => region.or(clip_bounds),
+    };
+
+    if let Some(viewport) = pipeline.viewport {
+        render_pass.set_viewport(
+            viewport.x,
+            viewport.y,
+            viewport.width,
+            viewport.height,
+            viewport.min_depth,
+            viewport.max_depth,
+        );
+    }
 
     if let Some(region) = region {
         render_pass.set_scissor_rect(
@@ -400,7 +1042,103 @@ fn draw<D>(
         );
     }
 
-    render_pass.draw(0..4, 0..pipeline.current_instances as u32);
+    pipeline.draw_instances_into_pass(instances, bind_group, &mut render_pass);
+}
+
+/// The transform, followed by a disabled clip, no effect, no fill and no
+/// alpha cutoff.
+fn initial_uniforms() -> [f32; 36] {
+    let mut uniforms = [0.0; 36];
+    uniforms[..16].copy_from_slice(&IDENTITY_MATRIX);
+    uniforms[16..24].copy_from_slice(&RoundedClip::uniforms(None));
+    uniforms[24..32].copy_from_slice(&Effect::uniforms(None, 0.0, None));
+
+    uniforms
+}
+
+fn create_sampler(
+    device: &wgpu::Device,
+    filter_method: wgpu::FilterMode,
+) -> wgpu::Sampler {
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        address_mode_w: wgpu::AddressMode::ClampToEdge,
+        mag_filter: filter_method,
+        min_filter: filter_method,
+        mipmap_filter: filter_method,
+        // The glyph cache has a single mip level
+        lod_min_clamp: 0.0,
+        lod_max_clamp: 0.0,
+        ..Default::default()
+    })
+}
+
+/// Returns the entries of the bind group layout used by the pipeline of a
+/// [`GlyphBrush`](struct.GlyphBrush.html): the uniform buffer, the sampler
+/// and the texture of the glyph cache, and the sampler and the texture of the
+/// fill.
+///
+/// A layout created with them can be provided to
+/// [`GlyphBrushBuilder::uniform_layout`](struct.GlyphBrushBuilder.html#method.uniform_layout).
+pub fn uniform_layout_entries() -> [wgpu::BindGroupLayoutEntry; 5] {
+    [
+        wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX
+                | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: wgpu::BufferSize::new(
+                    mem::size_of::<[f32; 36]>() as u64,
+                ),
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler {
+                filtering: true,
+                comparison: false,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 2,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float {
+                    filterable: false,
+                },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 3,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler {
+                filtering: true,
+                comparison: false,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 4,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float {
+                    filterable: true,
+                },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        },
+    ]
 }
 
 fn create_uniforms(
@@ -409,10 +1147,12 @@ fn create_uniforms(
     transform: &wgpu::Buffer,
     sampler: &wgpu::Sampler,
     cache: &wgpu::TextureView,
+    fill_sampler: &wgpu::Sampler,
+    fill: &wgpu::TextureView,
 ) -> wgpu::BindGroup {
     device.create_bind_group(&wgpu::BindGroupDescriptor {
         label: Some("wgpu_glyph::Pipeline uniforms"),
-        layout: layout,
+        layout,
         entries: &[
             wgpu::BindGroupEntry {
                 binding: 0,
@@ -430,39 +1170,211 @@ fn create_uniforms(
                 binding: 2,
                 resource: wgpu::BindingResource::TextureView(cache),
             },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::Sampler(fill_sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 4,
+                resource: wgpu::BindingResource::TextureView(fill),
+            },
         ],
     })
 }
 
+/// The primitive topology used to draw the quad of every glyph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// A strip of 2 triangles, drawn with 4 vertices and no index buffer.
+    TriangleStrip,
+    /// A list of 2 triangles, drawn with 6 indices into the 4 vertices of the
+    /// quad.
+    TriangleList,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Topology {
+    fn default() -> Self {
+        Topology::TriangleStrip
+    }
+}
+
+/// A glyph quad, as uploaded to the GPU.
+///
+/// Instances can be drawn directly with
+/// [`GlyphBrush::draw_instances`](struct.GlyphBrush.html#method.draw_instances).
 #[repr(C)]
-#[derive(Debug, Clone, Copy, Zeroable, Pod)]
+#[derive(Debug, Clone, Copy, PartialEq, Zeroable, Pod)]
 pub struct Instance {
     left_top: [f32; 3],
     right_bottom: [f32; 2],
     tex_left_top: [f32; 2],
     tex_right_bottom: [f32; 2],
     color: [f32; 4],
+    color_end: [f32; 4],
+    gradient_axis: [f32; 2],
+    rotation: [f32; 3],
+    clip: [f32; 4],
+    index: u32,
 }
 
 impl Instance {
-    const INITIAL_AMOUNT: usize = 50_000;
+    pub(crate) const INITIAL_AMOUNT: usize = 50_000;
 
+    /// Creates a new [`Instance`].
+    ///
+    /// - `bounds` is the rectangle covered by the glyph, in the same pixel
+    ///   coordinates used to position sections (i.e. before applying the
+    ///   transform provided when drawing).
+    /// - `tex_coords` is the rectangle of the glyph in the glyph cache, in
+    ///   normalized texture coordinates (from `0.0` to `1.0`).
+    /// - `z` is the depth of the glyph, also before applying the transform.
+    /// - `color` is the RGBA color of the glyph, in the
+    ///   [`ColorSpace`](enum.ColorSpace.html) of the `GlyphBrush`.
+    pub fn new(
+        bounds: Rect,
+        tex_coords: Rect,
+        z: f32,
+        color: [f32; 4],
+    ) -> Instance {
+        Instance {
+            left_top: [bounds.min.x, bounds.max.y, z],
+            right_bottom: [bounds.max.x, bounds.min.y],
+            tex_left_top: [tex_coords.min.x, tex_coords.max.y],
+            tex_right_bottom: [tex_coords.max.x, tex_coords.min.y],
+            color,
+            color_end: color,
+            gradient_axis: [0.0, 0.0],
+            rotation: [0.0, 0.0, 0.0],
+            clip: [0.0, 0.0, 0.0, 0.0],
+            index: 0,
+        }
+    }
+
+    /// Rotates the [`Instance`] by `angle` radians around the given `pivot`,
+    /// in the same pixel coordinates used to position sections, where
+    /// positive angles rotate clockwise.
+    pub fn with_rotation(mut self, pivot: (f32, f32), angle: f32) -> Instance {
+        self.rotation = [pivot.0, pivot.1, angle];
+        self
+    }
+
+    /// Clips the [`Instance`] to the given rectangle, in the same pixel
+    /// coordinates used to position sections.
+    ///
+    /// The clip is applied by the shader to every fragment, after rotating and
+    /// displacing the instance, so instances clipped to different rectangles
+    /// can be drawn together. An empty rectangle removes the clip.
+    pub fn with_clip(mut self, clip: Rect) -> Instance {
+        self.clip = [clip.min.x, clip.min.y, clip.max.x, clip.max.y];
+        self
+    }
+
+    /// Sets the index of the [`Instance`], available to the shaders.
+    ///
+    /// Glyphs of queued sections are numbered from `0` within their section,
+    /// in layout order. Other instances default to `0`.
+    pub fn with_index(mut self, index: u32) -> Instance {
+        self.index = index;
+        self
+    }
+
+    /// Returns whether the [`Instance`] covers no area at all, e.g. because it
+    /// was cropped out of its bounds completely.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.right_bottom[0] <= self.left_top[0]
+            || self.left_top[1] <= self.right_bottom[1]
+    }
+
+    /// Creates an [`Instance`] from a [`GlyphVertex`] produced by
+    /// `glyph_brush`, optionally cropping it to its `bounds`.
+    ///
+    /// [`GlyphVertex`]: glyph_brush::GlyphVertex
     pub fn from_vertex(
         glyph_brush::GlyphVertex {
-            mut tex_coords,
+            tex_coords,
             pixel_coords,
             bounds,
             extra,
         }: glyph_brush::GlyphVertex,
+        crop_to_bounds: bool,
+    ) -> Instance {
+        let (gl_rect, tex_coords) =
+            crop(pixel_coords, tex_coords, bounds, crop_to_bounds);
+
+        Instance::new(gl_rect, tex_coords, extra.z, extra.color)
+    }
+
+    pub(crate) fn from_glyph_vertex(
+        glyph_brush::GlyphVertex {
+            tex_coords,
+            pixel_coords,
+            bounds,
+            extra,
+        }: glyph_brush::GlyphVertex<'_, GlyphExtra>,
+        crop_to_bounds: bool,
     ) -> Instance {
-        let gl_bounds = bounds;
+        let (bounds, crop_to_bounds) = extra.style.crop(bounds, crop_to_bounds);
+        let (gl_rect, tex_coords) =
+            crop(pixel_coords, tex_coords, bounds, crop_to_bounds);
+        let gl_rect = extra.style.scale_rect(gl_rect);
+        let tex_coords = extra.style.mirror_tex_coords(tex_coords);
 
-        let mut gl_rect = Rect {
-            min: point(pixel_coords.min.x as f32, pixel_coords.min.y as f32),
-            max: point(pixel_coords.max.x as f32, pixel_coords.max.y as f32),
+        Instance::new(gl_rect, tex_coords, extra.extra.z, extra.extra.color)
+            .with_gradient(extra.gradient.as_ref())
+    }
+
+    /// Creates an [`Instance`] filling the given `bounds` with a solid
+    /// `color`, instead of sampling the glyph cache.
+    ///
+    /// Solid instances are marked with negative texture coordinates, so no
+    /// texel of the glyph cache has to be reserved for them.
+    pub fn solid(bounds: Rect, z: f32, color: [f32; 4]) -> Instance {
+        let solid = Rect {
+            min: point(-1.0, -1.0),
+            max: point(-1.0, -1.0),
         };
 
-        // handle overlapping bounds, modify uv_rect to preserve texture aspect
+        Instance::new(bounds, solid, z, color)
+    }
+
+    /// Replaces the color of the [`Instance`] with the given gradient, if any.
+    pub(crate) fn with_gradient(mut self, gradient: Option<&Span>) -> Instance {
+        if let Some(gradient) = gradient {
+            let bounds = Rect {
+                min: point(self.left_top[0], self.right_bottom[1]),
+                max: point(self.right_bottom[0], self.left_top[1]),
+            };
+
+            let (color, color_end, axis) = gradient.colors(bounds);
+
+            self.color = color;
+            self.color_end = color_end;
+            self.gradient_axis = axis;
+        }
+
+        self
+    }
+}
+
+/// Computes the rectangle covered by a glyph and its texture coordinates,
+/// optionally cropping them to the `bounds` of its section.
+fn crop(
+    pixel_coords: Rect,
+    mut tex_coords: Rect,
+    bounds: Rect,
+    crop_to_bounds: bool,
+) -> (Rect, Rect) {
+    let gl_bounds = bounds;
+
+    let mut gl_rect = Rect {
+        min: point(pixel_coords.min.x, pixel_coords.min.y),
+        max: point(pixel_coords.max.x, pixel_coords.max.y),
+    };
+
+    if crop_to_bounds {
+        // handle overlapping bounds, modify uv_rect to preserve texture
+        // aspect
         if gl_rect.max.x > gl_bounds.max.x {
             let old_width = gl_rect.width();
             gl_rect.max.x = gl_bounds.max.x;
@@ -490,13 +1402,57 @@ impl Instance {
             tex_coords.min.y = tex_coords.max.y
                 - tex_coords.height() * gl_rect.height() / old_height;
         }
+    }
 
-        Instance {
-            left_top: [gl_rect.min.x, gl_rect.max.y, extra.z],
-            right_bottom: [gl_rect.max.x, gl_rect.min.y],
-            tex_left_top: [tex_coords.min.x, tex_coords.max.y],
-            tex_right_bottom: [tex_coords.max.x, tex_coords.min.y],
-            color: extra.color,
+    (gl_rect, tex_coords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Rect {
+        Rect {
+            min: point(min_x, min_y),
+            max: point(max_x, max_y),
         }
     }
+
+    #[test]
+    fn crop_keeps_overflowing_glyph_when_disabled() {
+        let pixel_coords = rect(90.0, 0.0, 110.0, 20.0);
+        let tex_coords = rect(0.0, 0.0, 0.5, 0.5);
+        let bounds = rect(0.0, 0.0, 100.0, 100.0);
+
+        let (gl_rect, cropped) = crop(pixel_coords, tex_coords, bounds, false);
+
+        assert_eq!(gl_rect, pixel_coords);
+        assert_eq!(cropped, tex_coords);
+    }
+
+    #[test]
+    fn crop_crops_overflowing_glyph_and_its_texture() {
+        let pixel_coords = rect(90.0, 0.0, 110.0, 20.0);
+        let tex_coords = rect(0.0, 0.0, 0.5, 0.5);
+        let bounds = rect(0.0, 0.0, 100.0, 100.0);
+
+        let (gl_rect, cropped) = crop(pixel_coords, tex_coords, bounds, true);
+
+        assert_eq!(gl_rect, rect(90.0, 0.0, 100.0, 20.0));
+        assert_eq!(cropped, rect(0.0, 0.0, 0.25, 0.5));
+    }
+
+    #[test]
+    fn glyph_cropped_out_of_its_bounds_is_empty() {
+        let tex_coords = rect(0.0, 0.0, 0.5, 0.5);
+        let bounds = rect(50.0, 0.0, 100.0, 100.0);
+
+        let (left, left_tex) =
+            crop(rect(10.0, 0.0, 30.0, 20.0), tex_coords, bounds, true);
+        let (inside, inside_tex) =
+            crop(rect(60.0, 0.0, 80.0, 20.0), tex_coords, bounds, true);
+
+        assert!(Instance::new(left, left_tex, 0.0, [1.0; 4]).is_empty());
+        assert!(!Instance::new(inside, inside_tex, 0.0, [1.0; 4]).is_empty());
+    }
 }
@@ -0,0 +1,118 @@
+use super::Instance;
+
+use core::num::NonZeroU64;
+use std::mem;
+
+pub struct InstanceBuffer {
+    raw: wgpu::Buffer,
+    current_instances: usize,
+    supported_instances: usize,
+    max_instances: Option<usize>,
+}
+
+impl InstanceBuffer {
+    /// Creates a buffer able to hold `capacity` instances, growing on demand
+    /// up to `max_instances`, if any.
+    pub fn new(
+        device: &wgpu::Device,
+        capacity: usize,
+        max_instances: Option<usize>,
+    ) -> InstanceBuffer {
+        let capacity = max_instances
+            .map_or(capacity, |max_instances| capacity.min(max_instances))
+            .max(1);
+
+        InstanceBuffer {
+            raw: create_buffer(device, capacity),
+            current_instances: 0,
+            supported_instances: capacity,
+            max_instances,
+        }
+    }
+
+    /// Returns the bytes held by the buffer.
+    pub fn bytes(&self) -> u64 {
+        mem::size_of::<Instance>() as u64 * self.supported_instances as u64
+    }
+
+    pub fn clear(&mut self) {
+        self.current_instances = 0;
+    }
+
+    pub fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        instances: &[Instance],
+    ) -> Result<(), String> {
+        if instances.is_empty() {
+            self.current_instances = 0;
+            return Ok(());
+        }
+
+        if let Some(max_instances) = self.max_instances {
+            if instances.len() > max_instances {
+                self.current_instances = 0;
+
+                return Err(format!(
+                    "{} instances exceed the maximum of {} instances",
+                    instances.len(),
+                    max_instances
+                ));
+            }
+        }
+
+        if instances.len() > self.supported_instances {
+            self.raw = create_buffer(device, instances.len());
+            self.supported_instances = instances.len();
+        }
+
+        let instances_bytes = bytemuck::cast_slice(instances);
+
+        if let Some(size) = NonZeroU64::new(instances_bytes.len() as u64) {
+            let mut instances_view =
+                staging_belt.write_buffer(encoder, &self.raw, 0, size, device);
+
+            instances_view.copy_from_slice(instances_bytes);
+        }
+
+        self.current_instances = instances.len();
+
+        Ok(())
+    }
+
+    /// Draws every instance as a quad, using the given quad indices if the
+    /// pipeline uses a triangle list topology.
+    pub fn draw_into_pass<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        indices: Option<&'a wgpu::Buffer>,
+    ) {
+        let instances = 0..self.current_instances as u32;
+
+        render_pass.set_vertex_buffer(0, self.raw.slice(..));
+
+        match indices {
+            Some(indices) => {
+                render_pass.set_index_buffer(
+                    indices.slice(..),
+                    wgpu::IndexFormat::Uint16,
+                );
+                render_pass.draw_indexed(0..6, 0, instances);
+            }
+            None => {
+                render_pass.draw(0..4, instances);
+            }
+        }
+    }
+}
+
+fn create_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("wgpu_glyph::Pipeline instances"),
+        size: mem::size_of::<Instance>() as u64 * capacity as u64,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
@@ -3,16 +3,25 @@ use std::num::NonZeroU32;
 
 pub struct Cache {
     texture: wgpu::Texture,
+    format: wgpu::TextureFormat,
+    size: (u32, u32),
     pub(super) view: wgpu::TextureView,
     upload_buffer: wgpu::Buffer,
     upload_buffer_size: u64,
+    pending_data: Vec<u8>,
+    pending_regions: Vec<PendingRegion>,
 }
 
 impl Cache {
     const INITIAL_UPLOAD_BUFFER_SIZE: u64 =
         wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64 * 100;
 
-    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Cache {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Cache {
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("wgpu_glyph::Cache"),
             size: wgpu::Extent3d {
@@ -21,7 +30,7 @@ impl Cache {
                 depth_or_array_layers: 1,
             },
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::R8Unorm,
+            format,
             usage: wgpu::TextureUsages::COPY_DST
                 | wgpu::TextureUsages::TEXTURE_BINDING,
             mip_level_count: 1,
@@ -39,21 +48,35 @@ impl Cache {
 
         Cache {
             texture,
+            format,
+            size: (width, height),
             view,
             upload_buffer,
             upload_buffer_size: Self::INITIAL_UPLOAD_BUFFER_SIZE,
+            pending_data: Vec::new(),
+            pending_regions: Vec::new(),
         }
     }
 
-    pub fn update(
-        &mut self,
-        device: &wgpu::Device,
-        staging_belt: &mut wgpu::util::StagingBelt,
-        encoder: &mut wgpu::CommandEncoder,
-        offset: [u16; 2],
-        size: [u16; 2],
-        data: &[u8],
-    ) {
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// Returns the bytes held by the cache texture.
+    pub fn texture_bytes(&self) -> u64 {
+        let block_size = u64::from(self.format.describe().block_size);
+
+        u64::from(self.size.0) * u64::from(self.size.1) * block_size
+    }
+
+    /// Returns the bytes held by the upload buffer.
+    pub fn upload_bytes(&self) -> u64 {
+        self.upload_buffer_size
+    }
+
+    /// Queues a region of the cache to be updated with the given data on the
+    /// next [`flush`](#method.flush).
+    pub fn update(&mut self, offset: [u16; 2], size: [u16; 2], data: &[u8]) {
         let width = size[0] as usize;
         let height = size[1] as usize;
 
@@ -65,59 +88,192 @@ impl Cache {
         let padded_width_padding = (align - width % align) % align;
         let padded_width = width + padded_width_padding;
 
-        let padded_data_size = (padded_width * height) as u64;
+        // Every region starts at a multiple of the row alignment, which
+        // satisfies the buffer offset alignment of the copies
+        let buffer_offset = self.pending_data.len();
+
+        self.pending_data
+            .resize(buffer_offset + padded_width * height, 0);
+
+        for row in 0..height {
+            let start = buffer_offset + row * padded_width;
+
+            self.pending_data[start..start + width]
+                .copy_from_slice(&data[row * width..(row + 1) * width])
+        }
+
+        self.pending_regions.push(PendingRegion {
+            offset,
+            size,
+            buffer_offset: buffer_offset as u64,
+            padded_width: padded_width as u32,
+        });
+    }
+
+    /// Returns whether any queued region overlaps the given rectangle of
+    /// the cache, in normalized texture coordinates.
+    pub fn is_pending(&self, min: [f32; 2], max: [f32; 2]) -> bool {
+        self.pending_regions
+            .iter()
+            .any(|region| region.overlaps(self.size, min, max))
+    }
+
+    /// Returns whether any region is waiting to be uploaded.
+    pub fn has_pending(&self) -> bool {
+        !self.pending_regions.is_empty()
+    }
+
+    /// Uploads the regions queued since the last flush, up to the given
+    /// `budget` if any, using a single staging buffer write, and returns how
+    /// many were uploaded.
+    ///
+    /// Regions over the budget are kept for the next flush.
+    pub fn flush(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        budget: Option<usize>,
+    ) -> usize {
+        let count = budget.map_or(self.pending_regions.len(), |budget| {
+            budget.min(self.pending_regions.len())
+        });
+
+        if count == 0 {
+            return 0;
+        }
+
+        let data_size = self
+            .pending_regions
+            .get(count)
+            .map_or(self.pending_data.len() as u64, |next| next.buffer_offset);
 
-        if self.upload_buffer_size < padded_data_size {
+        if self.upload_buffer_size < data_size {
             self.upload_buffer =
                 device.create_buffer(&wgpu::BufferDescriptor {
                     label: Some("wgpu_glyph::Cache upload buffer"),
-                    size: padded_data_size,
+                    size: data_size,
                     usage: wgpu::BufferUsages::COPY_DST
                         | wgpu::BufferUsages::COPY_SRC,
                     mapped_at_creation: false,
                 });
 
-            self.upload_buffer_size = padded_data_size;
+            self.upload_buffer_size = data_size;
         }
 
-        let mut padded_data = staging_belt.write_buffer(
-            encoder,
-            &self.upload_buffer,
-            0,
-            NonZeroU64::new(padded_data_size).unwrap(),
-            device,
-        );
+        staging_belt
+            .write_buffer(
+                encoder,
+                &self.upload_buffer,
+                0,
+                NonZeroU64::new(data_size).unwrap(),
+                device,
+            )
+            .copy_from_slice(&self.pending_data[..data_size as usize]);
 
-        for row in 0..height {
-            padded_data[row * padded_width..row * padded_width + width]
-                .copy_from_slice(&data[row * width..(row + 1) * width])
-        }
-
-        // TODO: Move to use Queue for less buffer usage
-        encoder.copy_buffer_to_texture(
-            wgpu::ImageCopyBuffer {
-                buffer: &self.upload_buffer,
-                layout: wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: NonZeroU32::new(padded_width as u32),
-                    rows_per_image: NonZeroU32::new(height as u32),
+        for region in self.pending_regions.drain(..count) {
+            encoder.copy_buffer_to_texture(
+                wgpu::ImageCopyBuffer {
+                    buffer: &self.upload_buffer,
+                    layout: wgpu::ImageDataLayout {
+                        offset: region.buffer_offset,
+                        bytes_per_row: NonZeroU32::new(region.padded_width),
+                        rows_per_image: NonZeroU32::new(u32::from(
+                            region.size[1],
+                        )),
+                    },
                 },
-            },
-            wgpu::ImageCopyTexture {
-                texture: &self.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d {
-                    x: u32::from(offset[0]),
-                    y: u32::from(offset[1]),
-                    z: 0,
+                wgpu::ImageCopyTexture {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: u32::from(region.offset[0]),
+                        y: u32::from(region.offset[1]),
+                        z: 0,
+                    },
+                    aspect: wgpu::TextureAspect::All,
                 },
-                aspect: wgpu::TextureAspect::All,
-            },
-            wgpu::Extent3d {
-                width: size[0] as u32,
-                height: size[1] as u32,
-                depth_or_array_layers: 1,
-            },
-        );
+                wgpu::Extent3d {
+                    width: u32::from(region.size[0]),
+                    height: u32::from(region.size[1]),
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        // Every offset stays a multiple of the row alignment
+        let _ = self.pending_data.drain(..data_size as usize);
+
+        for region in &mut self.pending_regions {
+            region.buffer_offset -= data_size;
+        }
+
+        count
+    }
+}
+
+/// A region of the cache waiting to be uploaded.
+struct PendingRegion {
+    offset: [u16; 2],
+    size: [u16; 2],
+    buffer_offset: u64,
+    padded_width: u32,
+}
+
+impl PendingRegion {
+    /// Returns whether the region overlaps the given rectangle of a cache of
+    /// the given size, in normalized texture coordinates.
+    fn overlaps(
+        &self,
+        (width, height): (u32, u32),
+        min: [f32; 2],
+        max: [f32; 2],
+    ) -> bool {
+        let width = width as f32;
+        let height = height as f32;
+
+        let left = f32::from(self.offset[0]) / width;
+        let top = f32::from(self.offset[1]) / height;
+        let right = f32::from(self.offset[0] + self.size[0]) / width;
+        let bottom = f32::from(self.offset[1] + self.size[1]) / height;
+
+        min[0] < right && left < max[0] && min[1] < bottom && top < max[1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CACHE_SIZE: (u32, u32) = (100, 50);
+
+    fn region(offset: [u16; 2], size: [u16; 2]) -> PendingRegion {
+        PendingRegion {
+            offset,
+            size,
+            buffer_offset: 0,
+            padded_width: 0,
+        }
+    }
+
+    #[test]
+    fn regions_overlap_the_glyphs_they_hold() {
+        let region = region([10, 10], [20, 10]);
+
+        assert!(region.overlaps(CACHE_SIZE, [0.1, 0.2], [0.3, 0.4]));
+        assert!(region.overlaps(CACHE_SIZE, [0.15, 0.25], [0.2, 0.3]));
+        assert!(region.overlaps(CACHE_SIZE, [0.0, 0.0], [0.15, 0.25]));
+    }
+
+    #[test]
+    fn regions_do_not_overlap_neighbouring_glyphs() {
+        let region = region([10, 10], [20, 10]);
+
+        // Touching the edges of the region
+        assert!(!region.overlaps(CACHE_SIZE, [0.3, 0.2], [0.4, 0.4]));
+        assert!(!region.overlaps(CACHE_SIZE, [0.1, 0.0], [0.3, 0.2]));
+
+        // Far from the region
+        assert!(!region.overlaps(CACHE_SIZE, [0.5, 0.5], [0.6, 0.6]));
     }
 }
@@ -1,7 +1,26 @@
 /// A region of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Region {
     pub x: u32,
     pub y: u32,
     pub width: u32,
     pub height: u32,
 }
+
+impl Region {
+    /// Returns the region covered by both regions, which is empty if they do
+    /// not overlap.
+    pub(crate) fn intersection(self, other: Region) -> Region {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+
+        Region {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use glyph_brush::OwnedSection;
+use std::collections::BTreeMap;
+
+/// An identifier of a section retained by a
+/// [`GlyphBrush`](struct.GlyphBrush.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SectionId(u64);
+
+#[derive(Debug, Default)]
+pub struct Retained {
+    sections: BTreeMap<SectionId, OwnedSection>,
+    next_id: u64,
+}
+
+impl Retained {
+    pub fn insert(&mut self, section: OwnedSection) -> SectionId {
+        let id = SectionId(self.next_id);
+        self.next_id += 1;
+
+        let _ = self.sections.insert(id, section);
+
+        id
+    }
+
+    pub fn get_mut(&mut self, id: SectionId) -> Option<&mut OwnedSection> {
+        self.sections.get_mut(&id)
+    }
+
+    pub fn remove(&mut self, id: SectionId) -> Option<OwnedSection> {
+        self.sections.remove(&id)
+    }
+
+    /// Returns the retained sections in insertion order.
+    pub fn sections(&self) -> impl Iterator<Item = &OwnedSection> {
+        self.sections.values()
+    }
+}
@@ -0,0 +1,90 @@
+use glyph_brush::ab_glyph::Rect;
+use std::ops::Range;
+
+/// Returns the range of lines of a document visible in the given `viewport`,
+/// when every line is `line_height` pixels tall and the document is scrolled
+/// down by `scroll` pixels.
+///
+/// This allows drawing huge documents by only queueing the lines that can be
+/// seen. Lines partially visible at the edges of the viewport are included,
+/// and the range never goes past the `lines` of the document. Queue every
+/// line of the range at its [`line_position`](fn.line_position.html),
+/// cropped to the viewport with
+/// [`Style::crop_bounds`](struct.Style.html#structfield.crop_bounds), so the
+/// lines at the edges are cut like the rest of a scrollable area.
+pub fn visible_line_range(
+    viewport: Rect,
+    line_height: f32,
+    scroll: f32,
+    lines: usize,
+) -> Range<usize> {
+    if line_height <= 0.0 || viewport.height() <= 0.0 {
+        return 0..0;
+    }
+
+    let first = (scroll / line_height).floor().max(0.0) as usize;
+    let last =
+        ((scroll + viewport.height()) / line_height).ceil().max(0.0) as usize;
+
+    first.min(lines)..last.min(lines)
+}
+
+/// Returns the screen position of the top-left corner of the given `line` of
+/// a document drawn in the `viewport`, when every line is `line_height`
+/// pixels tall and the document is scrolled down by `scroll` pixels.
+///
+/// See [`visible_line_range`](fn.visible_line_range.html).
+pub fn line_position(
+    viewport: Rect,
+    line_height: f32,
+    scroll: f32,
+    line: usize,
+) -> (f32, f32) {
+    (
+        viewport.min.x,
+        viewport.min.y + line as f32 * line_height - scroll,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::point;
+
+    fn viewport(height: f32) -> Rect {
+        Rect {
+            min: point(10.0, 20.0),
+            max: point(110.0, 20.0 + height),
+        }
+    }
+
+    #[test]
+    fn includes_partially_visible_lines() {
+        assert_eq!(visible_line_range(viewport(100.0), 20.0, 30.0, 1000), 1..7);
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_document() {
+        assert_eq!(visible_line_range(viewport(100.0), 20.0, 150.0, 10), 7..10);
+        assert_eq!(
+            visible_line_range(viewport(100.0), 20.0, 500.0, 10),
+            10..10
+        );
+    }
+
+    #[test]
+    fn is_empty_without_height() {
+        assert_eq!(visible_line_range(viewport(0.0), 20.0, 0.0, 10), 0..0);
+        assert_eq!(visible_line_range(viewport(100.0), 0.0, 0.0, 10), 0..0);
+    }
+
+    #[test]
+    fn overscroll_above_the_document_starts_at_the_first_line() {
+        assert_eq!(visible_line_range(viewport(100.0), 20.0, -30.0, 10), 0..4);
+    }
+
+    #[test]
+    fn lines_are_positioned_relative_to_the_scroll() {
+        assert_eq!(line_position(viewport(100.0), 20.0, 30.0, 1), (10.0, 10.0));
+    }
+}
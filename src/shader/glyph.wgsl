@@ -1,11 +1,25 @@
 [[block]]
 struct Globals {
     transform: mat4x4<f32>;
+    // Center and half extents of the clip
+    clip_bounds: vec4<f32>;
+    // Corner radius of the clip and whether it is enabled
+    clip_params: vec4<f32>;
+    // Kind of effect, time, amplitude and frequency
+    effect: vec4<f32>;
+    // Speed of the effect, whether glyphs are faded in and the progress of the
+    // fade
+    effect_params: vec4<f32>;
+    // Whether the fill texture is enabled, the alpha cutoff, the gamma and the
+    // edge softness of the coverage
+    fragment: vec4<f32>;
 };
 
 [[group(0), binding(0)]] var<uniform> globals: Globals;
 [[group(0), binding(1)]] var font_sampler: sampler;
 [[group(0), binding(2)]] var font_tex: texture_2d<f32>;
+[[group(0), binding(3)]] var fill_sampler: sampler;
+[[group(0), binding(4)]] var fill_tex: texture_2d<f32>;
 
 struct VertexInput {
     [[builtin(vertex_index)]] vertex_index: u32;
@@ -14,16 +28,69 @@ struct VertexInput {
     [[location(2)]] tex_left_top: vec2<f32>;
     [[location(3)]] tex_right_bottom: vec2<f32>;
     [[location(4)]] color: vec4<f32>;
+    [[location(5)]] color_end: vec4<f32>;
+    [[location(6)]] gradient_axis: vec2<f32>;
+    // Pivot and angle of the rotation
+    [[location(7)]] rotation: vec3<f32>;
+    // The rectangle to clip to, if not empty
+    [[location(8)]] clip: vec4<f32>;
+    // The index of the glyph within its section
+    [[location(9)]] index: u32;
 };
 
 struct VertexOutput {
     [[builtin(position)]] position: vec4<f32>;
     [[location(0)]] f_tex_pos: vec2<f32>;
     [[location(1)]] f_color: vec4<f32>;
+    [[location(2)]] f_pos: vec2<f32>;
+    [[location(3), interpolate(flat)]] f_clip: vec4<f32>;
 };
 
-[[stage(vertex)]]
-fn vs_main(input: VertexInput) -> VertexOutput {
+// Returns a pseudo-random number between -1.0 and 1.0
+fn random(seed: f32) -> f32 {
+    return fract(sin(seed * 12.9898) * 43758.5453) * 2.0 - 1.0;
+}
+
+// Returns the displacement of a glyph, in pixels, caused by the effect
+fn displacement(index: f32) -> vec2<f32> {
+    var kind: f32 = globals.effect.x;
+    var time: f32 = globals.effect.y;
+    var amplitude: f32 = globals.effect.z;
+    var frequency: f32 = globals.effect.w;
+    var speed: f32 = globals.effect_params.x;
+
+    if (kind == 1.0) {
+        return vec2<f32>(0.0, amplitude * sin(index * frequency + time * speed));
+    }
+
+    if (kind == 2.0) {
+        var tick: f32 = floor(time * speed);
+
+        return amplitude * vec2<f32>(random(index + tick * 0.37), random(index * 1.7 + tick * 0.61));
+    }
+
+    return vec2<f32>(0.0, 0.0);
+}
+
+// Converts a color from sRGB to linear space, keeping its alpha
+fn srgb_to_linear(color: vec4<f32>) -> vec4<f32> {
+    let rgb = color.rgb;
+    let low = rgb / 12.92;
+    let high = pow((rgb + vec3<f32>(0.055, 0.055, 0.055)) / 1.055, vec3<f32>(2.4, 2.4, 2.4));
+
+    return vec4<f32>(select(high, low, rgb <= vec3<f32>(0.04045, 0.04045, 0.04045)), color.a);
+}
+
+// Converts a color from linear to sRGB space, keeping its alpha
+fn linear_to_srgb(color: vec4<f32>) -> vec4<f32> {
+    let rgb = color.rgb;
+    let low = rgb * 12.92;
+    let high = 1.055 * pow(rgb, vec3<f32>(1.0 / 2.4, 1.0 / 2.4, 1.0 / 2.4)) - vec3<f32>(0.055, 0.055, 0.055);
+
+    return vec4<f32>(select(high, low, rgb <= vec3<f32>(0.0031308, 0.0031308, 0.0031308)), color.a);
+}
+
+fn vertex(input: VertexInput) -> VertexOutput {
     var out: VertexOutput;
 
     var pos: vec2<f32> = vec2<f32>(0.0, 0.0);
@@ -31,15 +98,18 @@ fn vs_main(input: VertexInput) -> VertexOutput {
     var right: f32 = input.right_bottom.x;
     var top: f32 = input.left_top.y;
     var bottom: f32 = input.right_bottom.y;
+    var corner: vec2<f32> = vec2<f32>(0.0, 0.0);
 
     switch (i32(input.vertex_index)) {
         case 0: {
             pos = vec2<f32>(left, top);
             out.f_tex_pos = input.tex_left_top;
+            corner = vec2<f32>(0.0, 1.0);
         }
         case 1: {
             pos = vec2<f32>(right, top);
             out.f_tex_pos = vec2<f32>(input.tex_right_bottom.x, input.tex_left_top.y);
+            corner = vec2<f32>(1.0, 1.0);
         }
         case 2: {
             pos = vec2<f32>(left, bottom);
@@ -48,23 +118,170 @@ fn vs_main(input: VertexInput) -> VertexOutput {
         case 3: {
             pos = vec2<f32>(right, bottom);
             out.f_tex_pos = input.tex_right_bottom;
+            corner = vec2<f32>(1.0, 0.0);
         }
         default: {}
     }
 
-    out.f_color = input.color;
+    out.f_color = mix(input.color, input.color_end, dot(input.gradient_axis, corner));
+
+    if (input.rotation.z != 0.0) {
+        let offset = pos - input.rotation.xy;
+        let c = cos(input.rotation.z);
+        let s = sin(input.rotation.z);
+
+        pos = input.rotation.xy + vec2<f32>(offset.x * c - offset.y * s, offset.x * s + offset.y * c);
+    }
+
+    // Solid instances use negative texture coordinates and are not displaced
+    // nor faded
+    if (input.tex_left_top.x >= 0.0) {
+        pos = pos + displacement(f32(input.index));
+
+        if (globals.effect_params.y != 0.0) {
+            out.f_color.a = out.f_color.a * clamp(globals.effect_params.z - f32(input.index), 0.0, 1.0);
+        }
+    }
+
+    out.f_pos = pos;
+    out.f_clip = input.clip;
     out.position = globals.transform * vec4<f32>(pos, input.left_top.z, 1.0);
 
     return out;
 }
 
+// Keeps the colors as they are, when they are in the space of the target
+[[stage(vertex)]]
+fn vs_main(input: VertexInput) -> VertexOutput {
+    return vertex(input);
+}
+
+[[stage(vertex)]]
+fn vs_main_srgb_to_linear(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput = vertex(input);
+    out.f_color = srgb_to_linear(out.f_color);
+
+    return out;
+}
+
+[[stage(vertex)]]
+fn vs_main_linear_to_srgb(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput = vertex(input);
+    out.f_color = linear_to_srgb(out.f_color);
+
+    return out;
+}
+
+// Returns the coverage of the rounded clip at the given pixel, using the
+// signed distance to a rounded box
+fn clip_alpha(position: vec2<f32>) -> f32 {
+    if (globals.clip_params.y == 0.0) {
+        return 1.0;
+    }
+
+    let radius = globals.clip_params.x;
+    let q = abs(position - globals.clip_bounds.xy) - globals.clip_bounds.zw + vec2<f32>(radius, radius);
+    let distance = length(max(q, vec2<f32>(0.0, 0.0))) + min(max(q.x, q.y), 0.0) - radius;
+
+    return clamp(0.5 - distance, 0.0, 1.0);
+}
+
+// Returns whether the given position is out of the clip of its instance
+fn clipped(position: vec2<f32>, clip: vec4<f32>) -> bool {
+    if (clip.z <= clip.x || clip.w <= clip.y) {
+        return false;
+    }
+
+    return any(position < clip.xy) || any(position > clip.zw);
+}
+
+// Remaps the coverage with an S-shaped curve around half coverage, whose
+// contrast is the inverse of the edge softness
+fn soften(alpha: f32) -> f32 {
+    let softness = globals.fragment.w;
+
+    if (softness <= 0.0 || softness == 1.0) {
+        return alpha;
+    }
+
+    let low = pow(alpha, 1.0 / softness);
+    let high = pow(1.0 - alpha, 1.0 / softness);
+
+    return low / (low + high);
+}
+
+// Returns the color of the fill texture at the given pixel, repeating it
+// across the target
+fn fill_color(position: vec2<f32>, solid: bool) -> vec4<f32> {
+    let size = vec2<f32>(textureDimensions(fill_tex));
+    let color = textureSample(fill_tex, fill_sampler, position / size);
+
+    // Solid instances are not filled
+    if (globals.fragment.x == 0.0 || solid) {
+        return vec4<f32>(1.0, 1.0, 1.0, 1.0);
+    }
+
+    return color;
+}
+
 [[stage(fragment)]]
 fn fs_main(input: VertexOutput) -> [[location(0)]] vec4<f32> {
     var alpha: f32 = textureSample(font_tex, font_sampler, input.f_tex_pos).r;
+    let fill = fill_color(input.position.xy, input.f_tex_pos.x < 0.0);
+
+    // Solid instances use negative texture coordinates
+    if (input.f_tex_pos.x < 0.0) {
+        alpha = 1.0;
+    }
+
+    if (globals.fragment.z > 0.0) {
+        alpha = pow(alpha, globals.fragment.z);
+    }
+
+    alpha = soften(alpha);
+
+    alpha = alpha * clip_alpha(input.position.xy);
+
+    // Coverage is either dropped or made opaque with an alpha cutoff
+    if (globals.fragment.y > 0.0) {
+        alpha = step(globals.fragment.y, alpha);
+    }
+
+    if (alpha <= 0.0 || clipped(input.f_pos, input.f_clip)) {
+        discard;
+    }
+
+    return input.f_color * fill * vec4<f32>(1.0, 1.0, 1.0, alpha);
+}
+
+[[stage(fragment)]]
+fn fs_main_premultiplied(input: VertexOutput) -> [[location(0)]] vec4<f32> {
+    var alpha: f32 = textureSample(font_tex, font_sampler, input.f_tex_pos).r;
+    let fill = fill_color(input.position.xy, input.f_tex_pos.x < 0.0);
+
+    // Solid instances use negative texture coordinates
+    if (input.f_tex_pos.x < 0.0) {
+        alpha = 1.0;
+    }
+
+    if (globals.fragment.z > 0.0) {
+        alpha = pow(alpha, globals.fragment.z);
+    }
 
-    if (alpha <= 0.0) {
+    alpha = soften(alpha);
+
+    alpha = alpha * clip_alpha(input.position.xy);
+
+    // Coverage is either dropped or made opaque with an alpha cutoff
+    if (globals.fragment.y > 0.0) {
+        alpha = step(globals.fragment.y, alpha);
+    }
+
+    if (alpha <= 0.0 || clipped(input.f_pos, input.f_clip)) {
         discard;
     }
 
-    return input.f_color * vec4<f32>(1.0, 1.0, 1.0, alpha);
+    var color: vec4<f32> = input.f_color * fill * vec4<f32>(1.0, 1.0, 1.0, alpha);
+
+    return vec4<f32>(color.rgb * color.a, color.a);
 }
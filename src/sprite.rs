@@ -0,0 +1,103 @@
+use crate::Instance;
+
+use glyph_brush::ab_glyph::{point, Rect};
+use std::collections::HashMap;
+
+/// A glyph of a [`SpriteFont`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteGlyph {
+    /// The rectangle of the glyph in the texture of the font, in normalized
+    /// texture coordinates (from `0.0` to `1.0`).
+    pub tex_coords: Rect,
+    /// The size of the glyph quad, in pixels.
+    pub size: (f32, f32),
+    /// The horizontal distance to the next glyph, in pixels.
+    pub advance: f32,
+}
+
+/// A font pre-rendered into a texture of your own, drawn with the pipeline of
+/// a [`GlyphBrush`](struct.GlyphBrush.html).
+///
+/// Create one with
+/// [`GlyphBrush::create_sprite_font`](struct.GlyphBrush.html#method.create_sprite_font)
+/// and draw the instances it lays out with
+/// [`GlyphBrush::draw_sprites`](struct.GlyphBrush.html#method.draw_sprites).
+pub struct SpriteFont {
+    pub(crate) bind_group: wgpu::BindGroup,
+    glyphs: HashMap<char, SpriteGlyph>,
+    line_height: f32,
+}
+
+impl SpriteFont {
+    pub(crate) fn new(
+        bind_group: wgpu::BindGroup,
+        glyphs: HashMap<char, SpriteGlyph>,
+        line_height: f32,
+    ) -> SpriteFont {
+        SpriteFont {
+            bind_group,
+            glyphs,
+            line_height,
+        }
+    }
+
+    /// Returns the glyph of the given character, if the font has one.
+    pub fn glyph(&self, c: char) -> Option<&SpriteGlyph> {
+        self.glyphs.get(&c)
+    }
+
+    /// Lays out the given text in a single line per `\n`, starting at the
+    /// top-left `position` in pixels and multiplying the sizes of the glyphs
+    /// by `scale`.
+    ///
+    /// Characters without a glyph are skipped.
+    pub fn layout(
+        &self,
+        text: &str,
+        position: (f32, f32),
+        scale: f32,
+        color: [f32; 4],
+        z: f32,
+    ) -> Vec<Instance> {
+        let (mut x, mut y) = position;
+        let mut instances = Vec::with_capacity(text.len());
+
+        for c in text.chars() {
+            if c == '\n' {
+                x = position.0;
+                y += self.line_height * scale;
+                continue;
+            }
+
+            if let Some(glyph) = self.glyphs.get(&c) {
+                let bounds = Rect {
+                    min: point(x, y),
+                    max: point(
+                        x + glyph.size.0 * scale,
+                        y + glyph.size.1 * scale,
+                    ),
+                };
+
+                instances.push(Instance::new(
+                    bounds,
+                    glyph.tex_coords,
+                    z,
+                    color,
+                ));
+
+                x += glyph.advance * scale;
+            }
+        }
+
+        instances
+    }
+}
+
+impl std::fmt::Debug for SpriteFont {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpriteFont")
+            .field("glyphs", &self.glyphs)
+            .field("line_height", &self.line_height)
+            .finish()
+    }
+}
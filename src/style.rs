@@ -0,0 +1,408 @@
+use crate::extra::GlyphExtra;
+use crate::gradient::Gradient;
+use crate::Instance;
+
+use glyph_brush::ab_glyph::{point, Font, Rect, ScaleFont};
+use glyph_brush::{GlyphPositioner, Section, SectionGeometry, SectionGlyph};
+use std::hash::{Hash, Hasher};
+
+/// Styling applied to a whole section, when queued with
+/// [`queue_styled`](struct.GlyphBrush.html#method.queue_styled).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Style {
+    /// A color gradient replacing the colors of the texts of the section.
+    pub gradient: Option<Gradient>,
+    /// Whether to draw a line below the baseline of the texts.
+    pub underline: bool,
+    /// Whether to draw a line through the middle of the texts.
+    pub strikethrough: bool,
+    /// Extra space added between consecutive glyphs, relative to their
+    /// horizontal scale (e.g. `0.1` adds a tenth of the scale).
+    ///
+    /// It is applied after line breaking, so lines may grow past the bounds
+    /// of the section.
+    pub tracking: f32,
+    /// A factor multiplying the advances of the glyphs, but not their size,
+    /// to draw condensed (e.g. `0.8`) or expanded (e.g. `1.2`) text.
+    ///
+    /// Like tracking, it is applied after line breaking. Defaults to `1.0`.
+    pub advance_scale: Option<f32>,
+    /// Whether to stretch the whitespace of wrapped lines so they fill the
+    /// width of the bounds of the section.
+    ///
+    /// The last line of every paragraph, lines without whitespace and
+    /// sections laid out in a single line are left untouched.
+    pub justify: bool,
+    /// Whether to give every digit the advance of the widest digit of its
+    /// font, so the digits of numbers in different lines line up, even with
+    /// proportional fonts.
+    ///
+    /// Digits are centered in the space they get, and other characters keep
+    /// their advance. Like tracking, it is applied after line breaking.
+    pub tabular_figures: bool,
+    /// The maximum amount of lines of the section to draw. The lines past it
+    /// are dropped.
+    ///
+    /// Only lines with glyphs are counted, so empty lines between paragraphs
+    /// do not count towards it.
+    pub max_lines: Option<usize>,
+    /// Whether to end the last line drawn with an ellipsis when lines are
+    /// dropped because of [`max_lines`](#structfield.max_lines).
+    ///
+    /// Glyphs are removed from the end of the line, even in the middle of a
+    /// word, until the ellipsis fits in the width of the bounds, and then any
+    /// trailing whitespace is removed too. An ellipsis wider than the bounds
+    /// replaces the whole line. Fonts without an ellipsis get three periods
+    /// instead.
+    pub ellipsis: bool,
+    /// A color to fill the bounds of the section with, behind its glyphs.
+    ///
+    /// Backgrounds are drawn before all the glyphs queued in the same draw.
+    pub background: Option<[f32; 4]>,
+    /// The space added around the bounds of the section by its background,
+    /// in pixels.
+    pub padding: f32,
+    /// The maximum amount of glyphs of the section to draw, in layout order.
+    ///
+    /// Only glyphs with a visible outline are counted. The background and
+    /// decorations of the section are still drawn in full. Changing it does
+    /// not lay out the section again.
+    pub reveal: Option<usize>,
+    /// A depth replacing the `z` of all the texts of the section, useful to
+    /// layer sections explicitly when using a depth stencil state.
+    ///
+    /// It is passed to the transform as is, so with an
+    /// [`orthographic_projection`](fn.orthographic_projection.html) it becomes
+    /// the depth in normalized device coordinates, from `0.0` to `1.0`.
+    pub z: Option<f32>,
+    /// Bounds to crop the glyphs and decorations of the section to, in
+    /// pixels, instead of the bounds of the section.
+    ///
+    /// This allows wrapping text at the bounds of the section while cropping
+    /// it somewhere else, e.g. to the viewport of a scrollable area. The
+    /// glyphs are cropped even if
+    /// [`crop_to_bounds`](struct.GlyphBrushBuilder.html#method.crop_to_bounds)
+    /// is disabled.
+    pub crop_bounds: Option<Rect>,
+    /// A positive factor scaling the glyphs, background and decorations of
+    /// the section around the [`scale_pivot`](#structfield.scale_pivot) when
+    /// drawn, e.g. to animate it.
+    ///
+    /// The section is laid out and its glyphs are rasterized at the scale of
+    /// its texts, so changing it every frame neither lays out the section
+    /// again nor uploads new glyphs. Glyphs drawn much bigger than their
+    /// rasterized size look blurry.
+    pub scale: Option<f32>,
+    /// The point to scale and mirror the section around, in pixels. Defaults
+    /// to the center of the bounds of its glyphs.
+    pub scale_pivot: Option<(f32, f32)>,
+    /// Whether to mirror the glyphs, background and decorations of the
+    /// section horizontally around the [`scale_pivot`](#structfield.scale_pivot),
+    /// e.g. to draw reflections.
+    ///
+    /// The glyphs are mirrored too, reading backwards, while their quads keep
+    /// their winding, so they are drawn regardless of the culling set with
+    /// [`cull_mode`](struct.GlyphBrushBuilder.html#method.cull_mode).
+    pub mirror_x: bool,
+    /// Whether to mirror the section vertically, like
+    /// [`mirror_x`](#structfield.mirror_x) does horizontally.
+    pub mirror_y: bool,
+}
+
+impl Style {
+    #[inline]
+    pub fn with_gradient(mut self, gradient: Gradient) -> Self {
+        self.gradient = Some(gradient);
+        self
+    }
+
+    #[inline]
+    pub fn with_underline(mut self, underline: bool) -> Self {
+        self.underline = underline;
+        self
+    }
+
+    #[inline]
+    pub fn with_strikethrough(mut self, strikethrough: bool) -> Self {
+        self.strikethrough = strikethrough;
+        self
+    }
+
+    #[inline]
+    pub fn with_tracking(mut self, tracking: f32) -> Self {
+        self.tracking = tracking;
+        self
+    }
+
+    #[inline]
+    pub fn with_advance_scale(mut self, advance_scale: f32) -> Self {
+        self.advance_scale = Some(advance_scale);
+        self
+    }
+
+    #[inline]
+    pub fn with_justify(mut self, justify: bool) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    #[inline]
+    pub fn with_tabular_figures(mut self, tabular_figures: bool) -> Self {
+        self.tabular_figures = tabular_figures;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    #[inline]
+    pub fn with_ellipsis(mut self, ellipsis: bool) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    #[inline]
+    pub fn with_background(mut self, color: [f32; 4]) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    #[inline]
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    #[inline]
+    pub fn with_reveal(mut self, reveal: usize) -> Self {
+        self.reveal = Some(reveal);
+        self
+    }
+
+    #[inline]
+    pub fn with_z(mut self, z: f32) -> Self {
+        self.z = Some(z);
+        self
+    }
+
+    #[inline]
+    pub fn with_crop_bounds(mut self, crop_bounds: Rect) -> Self {
+        self.crop_bounds = Some(crop_bounds);
+        self
+    }
+
+    #[inline]
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    #[inline]
+    pub fn with_scale_pivot(mut self, pivot: (f32, f32)) -> Self {
+        self.scale_pivot = Some(pivot);
+        self
+    }
+
+    #[inline]
+    pub fn with_mirror_x(mut self, mirror_x: bool) -> Self {
+        self.mirror_x = mirror_x;
+        self
+    }
+
+    #[inline]
+    pub fn with_mirror_y(mut self, mirror_y: bool) -> Self {
+        self.mirror_y = mirror_y;
+        self
+    }
+
+    /// Returns whether the style scales or mirrors the section, which needs
+    /// a pivot.
+    pub(crate) fn is_transformed(&self) -> bool {
+        self.scale.is_some() || self.mirror_x || self.mirror_y
+    }
+
+    /// Returns the bounds to crop to, given the ones of the section, and
+    /// whether cropping is enabled.
+    pub(crate) fn crop(
+        &self,
+        bounds: Rect,
+        crop_to_bounds: bool,
+    ) -> (Rect, bool) {
+        match self.crop_bounds {
+            Some(crop_bounds) => (crop_bounds, true),
+            None => (bounds, crop_to_bounds),
+        }
+    }
+
+    /// Scales and mirrors the given rectangle around the pivot of the style,
+    /// if it has one.
+    pub(crate) fn scale_rect(&self, rect: Rect) -> Rect {
+        let (x, y) = match self.scale_pivot {
+            Some(pivot) => pivot,
+            None => return rect,
+        };
+
+        let scale = self.scale.unwrap_or(1.0);
+        let scale_x = if self.mirror_x { -scale } else { scale };
+        let scale_y = if self.mirror_y { -scale } else { scale };
+
+        let left = x + (rect.min.x - x) * scale_x;
+        let right = x + (rect.max.x - x) * scale_x;
+        let top = y + (rect.min.y - y) * scale_y;
+        let bottom = y + (rect.max.y - y) * scale_y;
+
+        Rect {
+            min: point(left.min(right), top.min(bottom)),
+            max: point(left.max(right), top.max(bottom)),
+        }
+    }
+
+    /// Swaps the edges of the given texture coordinates on the mirrored axes,
+    /// so glyphs are sampled mirrored while their quads keep their winding.
+    pub(crate) fn mirror_tex_coords(&self, mut tex_coords: Rect) -> Rect {
+        if self.mirror_x {
+            std::mem::swap(&mut tex_coords.min.x, &mut tex_coords.max.x);
+        }
+
+        if self.mirror_y {
+            std::mem::swap(&mut tex_coords.min.y, &mut tex_coords.max.y);
+        }
+
+        tex_coords
+    }
+}
+
+impl Hash for Style {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.gradient.hash(state);
+        self.underline.hash(state);
+        self.strikethrough.hash(state);
+        self.tracking.to_bits().hash(state);
+        self.advance_scale.map(f32::to_bits).hash(state);
+        self.justify.hash(state);
+        self.tabular_figures.hash(state);
+        self.max_lines.hash(state);
+        self.ellipsis.hash(state);
+        self.background
+            .map(|color| color.map(f32::to_bits))
+            .hash(state);
+        self.padding.to_bits().hash(state);
+        self.reveal.hash(state);
+        self.z.map(f32::to_bits).hash(state);
+        self.crop_bounds
+            .map(|rect| {
+                [rect.min.x, rect.min.y, rect.max.x, rect.max.y]
+                    .map(f32::to_bits)
+            })
+            .hash(state);
+        self.scale.map(f32::to_bits).hash(state);
+        self.scale_pivot
+            .map(|(x, y)| [x.to_bits(), y.to_bits()])
+            .hash(state);
+        self.mirror_x.hash(state);
+        self.mirror_y.hash(state);
+    }
+}
+
+/// Builds the instance filling the padded `bounds` of a section with its
+/// background color, if it has any.
+pub fn background(
+    section: &Section<'_, GlyphExtra>,
+    bounds: Rect,
+) -> Option<Instance> {
+    let extra = &section.text.first()?.extra;
+    let color = extra.style.background?;
+    let padding = extra.style.padding;
+
+    let rect = extra.style.scale_rect(Rect {
+        min: point(bounds.min.x - padding, bounds.min.y - padding),
+        max: point(bounds.max.x + padding, bounds.max.y + padding),
+    });
+
+    Some(Instance::solid(rect, extra.extra.z, color))
+}
+
+/// Builds the line instances decorating the given glyphs of a section.
+///
+/// A line is drawn for every run of consecutive glyphs of the same text found
+/// on the same line. `ab_glyph` does not expose the underline and
+/// strikethrough metrics of fonts, so the lines are placed and sized relative
+/// to the ascent, descent and scale of the first glyph of every run.
+pub fn decorations<F: Font>(
+    section: &Section<'_, GlyphExtra>,
+    glyphs: &[SectionGlyph],
+    fonts: &[F],
+    crop_to_bounds: bool,
+) -> Vec<Instance> {
+    let bounds = section.layout.bounds_rect(&SectionGeometry::from(section));
+    let mut instances = Vec::new();
+
+    let mut glyphs = glyphs.iter().peekable();
+
+    while let Some(first) = glyphs.next() {
+        let font = fonts[first.font_id.0].as_scaled(first.glyph.scale);
+        let baseline = first.glyph.position.y;
+
+        let mut left = first.glyph.position.x;
+        let mut right = left + font.h_advance(first.glyph.id);
+
+        while let Some(next) = glyphs.peek() {
+            if next.section_index != first.section_index
+                || next.glyph.position.y != baseline
+            {
+                break;
+            }
+
+            let advance = fonts[next.font_id.0]
+                .as_scaled(next.glyph.scale)
+                .h_advance(next.glyph.id);
+
+            left = left.min(next.glyph.position.x);
+            right = right.max(next.glyph.position.x + advance);
+
+            let _ = glyphs.next();
+        }
+
+        let extra = &section.text[first.section_index].extra;
+        let (bounds, crop_to_bounds) = extra.style.crop(bounds, crop_to_bounds);
+        let thickness = (first.glyph.scale.y / 16.0).max(1.0);
+
+        let mut lines = Vec::with_capacity(2);
+
+        if extra.style.underline {
+            lines.push(baseline - font.descent() * 0.4);
+        }
+
+        if extra.style.strikethrough {
+            lines.push(baseline - font.ascent() * 0.3);
+        }
+
+        for center in lines {
+            let mut rect = Rect {
+                min: point(left, center - thickness / 2.0),
+                max: point(right, center + thickness / 2.0),
+            };
+
+            if crop_to_bounds {
+                rect.min.x = rect.min.x.max(bounds.min.x);
+                rect.min.y = rect.min.y.max(bounds.min.y);
+                rect.max.x = rect.max.x.min(bounds.max.x);
+                rect.max.y = rect.max.y.min(bounds.max.y);
+            }
+
+            let rect = extra.style.scale_rect(rect);
+            let instance =
+                Instance::solid(rect, extra.extra.z, extra.extra.color)
+                    .with_gradient(extra.gradient.as_ref());
+
+            if !instance.is_empty() {
+                instances.push(instance);
+            }
+        }
+    }
+
+    instances
+}
@@ -0,0 +1,97 @@
+use glyph_brush::{OwnedSection, Section};
+
+/// Expands the tabs of a [`Section`] into spaces, aligning them to tab stops
+/// placed every `width` characters.
+///
+/// Columns are counted in characters from the start of each line, carrying
+/// over between the different texts of the section.
+///
+/// Returns `None` if the section does not contain any tabs.
+pub fn expand(section: &Section<'_>, width: usize) -> Option<OwnedSection> {
+    if !section.text.iter().any(|text| text.text.contains('\t')) {
+        return None;
+    }
+
+    let mut section = section.to_owned();
+    let mut column = 0;
+
+    for text in &mut section.text {
+        let mut expanded = String::with_capacity(text.text.len());
+
+        for c in text.text.chars() {
+            match c {
+                '\t' => {
+                    let spaces = if width == 0 {
+                        0
+                    } else {
+                        width - column % width
+                    };
+
+                    for _ in 0..spaces {
+                        expanded.push(' ');
+                    }
+
+                    column += spaces;
+                }
+                '\n' => {
+                    expanded.push(c);
+                    column = 0;
+                }
+                _ => {
+                    expanded.push(c);
+                    column += 1;
+                }
+            }
+        }
+
+        text.text = expanded;
+    }
+
+    Some(section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::Text;
+
+    fn expanded(texts: &[&str], width: usize) -> Option<Vec<String>> {
+        let section = Section::default()
+            .with_text(texts.iter().map(|text| Text::new(text)).collect());
+
+        expand(&section, width).map(|section| {
+            section.text.into_iter().map(|text| text.text).collect()
+        })
+    }
+
+    #[test]
+    fn sections_without_tabs_are_not_expanded() {
+        assert_eq!(expanded(&["no tabs"], 4), None);
+    }
+
+    #[test]
+    fn tab_at_line_start_fills_a_whole_stop() {
+        assert_eq!(
+            expanded(&["\ta\n\tb"], 4),
+            Some(vec![String::from("    a\n    b")])
+        );
+    }
+
+    #[test]
+    fn multibyte_characters_count_as_one_column() {
+        assert_eq!(expanded(&["é€\tx"], 4), Some(vec![String::from("é€  x")]));
+    }
+
+    #[test]
+    fn columns_carry_over_between_texts() {
+        assert_eq!(
+            expanded(&["abc", "\td"], 4),
+            Some(vec![String::from("abc"), String::from(" d")])
+        );
+    }
+
+    #[test]
+    fn zero_width_removes_tabs() {
+        assert_eq!(expanded(&["a\tb"], 0), Some(vec![String::from("ab")]));
+    }
+}
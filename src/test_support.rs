@@ -0,0 +1,208 @@
+//! Helpers to render text without a window, e.g. in image-based tests.
+use crate::GlyphBrush;
+
+use core::hash::BuildHasher;
+use glyph_brush::ab_glyph::Font;
+use std::num::NonZeroU32;
+
+/// A `wgpu::Device` and `wgpu::Queue` created without any surface.
+#[derive(Debug)]
+pub struct Headless {
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+}
+
+impl Headless {
+    /// Requests a device of any available adapter, blocking until it is
+    /// created.
+    pub fn new() -> Result<Headless, String> {
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+
+        let adapter = futures::executor::block_on(instance.request_adapter(
+            &wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                force_fallback_adapter: false,
+                compatible_surface: None,
+            },
+        ))
+        .ok_or_else(|| String::from("No adapter is available"))?;
+
+        let (device, queue) = futures::executor::block_on(
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None),
+        )
+        .map_err(|error| {
+            format!("The device could not be created: {}", error)
+        })?;
+
+        Ok(Headless { device, queue })
+    }
+
+    /// Draws the sections queued in the `glyph_brush` onto a new texture of
+    /// the given size and reads its pixels back, row by row without padding.
+    ///
+    /// The `render_format` must be the one provided on creation of the
+    /// `GlyphBrush`, and it must have 4 bytes per pixel, like
+    /// `wgpu::TextureFormat::Rgba8UnormSrgb`.
+    pub fn render<F, H>(
+        &self,
+        glyph_brush: &mut GlyphBrush<(), F, H>,
+        render_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, String>
+    where
+        F: Font + Sync,
+        H: BuildHasher,
+    {
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("wgpu_glyph::test_support target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: render_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC,
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+        let mut encoder = self.device.create_command_encoder(
+            &wgpu::CommandEncoderDescriptor {
+                label: Some("wgpu_glyph::test_support encoder"),
+            },
+        );
+
+        let _ = glyph_brush.draw_queued(
+            &self.device,
+            &mut staging_belt,
+            &mut encoder,
+            &view,
+            width,
+            height,
+        )?;
+
+        staging_belt.finish();
+        self.queue.submit(Some(encoder.finish()));
+
+        self.read_texture(&texture, width, height)
+    }
+
+    /// Draws the sections queued in the `glyph_brush` with
+    /// [`GlyphBrush::draw_to_texture`](../struct.GlyphBrush.html#method.draw_to_texture)
+    /// and reads the pixels of the texture back, row by row without padding.
+    ///
+    /// The render format of the `GlyphBrush` must have 4 bytes per pixel.
+    pub fn render_with_background<F, H>(
+        &self,
+        glyph_brush: &mut GlyphBrush<(), F, H>,
+        width: u32,
+        height: u32,
+        background: wgpu::Color,
+    ) -> Result<Vec<u8>, String>
+    where
+        F: Font + Sync,
+        H: BuildHasher,
+    {
+        let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+        let mut encoder = self.device.create_command_encoder(
+            &wgpu::CommandEncoderDescriptor {
+                label: Some("wgpu_glyph::test_support encoder"),
+            },
+        );
+
+        let texture = glyph_brush.draw_to_texture(
+            &self.device,
+            &mut staging_belt,
+            &mut encoder,
+            width,
+            height,
+            background,
+        )?;
+
+        staging_belt.finish();
+        self.queue.submit(Some(encoder.finish()));
+
+        self.read_texture(&texture, width, height)
+    }
+
+    /// Reads the pixels of a texture of the given size back, row by row
+    /// without padding, blocking until all the submitted work is done.
+    ///
+    /// The texture must have the `COPY_SRC` usage and a format with 4 bytes
+    /// per pixel.
+    pub fn read_texture(
+        &self,
+        texture: &wgpu::Texture,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, String> {
+        const BYTES_PER_PIXEL: u32 = 4;
+
+        // Copied rows must be aligned to COPY_BYTES_PER_ROW_ALIGNMENT
+        let row_width = width * BYTES_PER_PIXEL;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_row_width = row_width + (align - row_width % align) % align;
+
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("wgpu_glyph::test_support output"),
+            size: u64::from(padded_row_width * height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(
+            &wgpu::CommandEncoderDescriptor {
+                label: Some("wgpu_glyph::test_support readback encoder"),
+            },
+        );
+
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &output,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(padded_row_width),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = output.slice(..);
+        let mapping = slice.map_async(wgpu::MapMode::Read);
+
+        self.device.poll(wgpu::Maintain::Wait);
+
+        futures::executor::block_on(mapping).map_err(|error| {
+            format!("The rendered pixels could not be read: {}", error)
+        })?;
+
+        let pixels = slice
+            .get_mapped_range()
+            .chunks(padded_row_width as usize)
+            .flat_map(|row| row[..row_width as usize].iter().copied())
+            .collect();
+
+        output.unmap();
+
+        Ok(pixels)
+    }
+}
@@ -0,0 +1,757 @@
+use glyph_brush::ab_glyph::{point, Font, GlyphId, Rect, ScaleFont};
+use glyph_brush::{
+    BuiltInLineBreaker, GlyphPositioner, HorizontalAlign, Layout,
+    SectionGeometry, SectionGlyph, SectionText, ToSectionText,
+};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// A [`Layout`] adding extra space between consecutive glyphs of every line,
+/// scaling their advances and optionally justifying wrapped lines.
+///
+/// The space is added after line breaking, so lines may grow past the bounds
+/// of their section. The added space is `tracking` times the horizontal scale
+/// of every glyph, the distance between glyphs is multiplied by
+/// `advance_scale`, and lines are shifted to keep their horizontal alignment.
+///
+/// When justifying, the space left in every wrapped line, except the last one
+/// of each paragraph, is spread evenly across its whitespace.
+///
+/// With tabular figures, every digit gets the advance of the widest digit of
+/// its font before any space is added.
+///
+/// Lines past `max_lines` are dropped before the space is added, and the
+/// last line kept may then end with an ellipsis fitting in the bounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tracking {
+    layout: Layout<BuiltInLineBreaker>,
+    tracking: f32,
+    advance_scale: f32,
+    justify: bool,
+    tabular_figures: bool,
+    max_lines: Option<usize>,
+    ellipsis: bool,
+}
+
+impl Tracking {
+    pub fn new(
+        layout: Layout<BuiltInLineBreaker>,
+        tracking: f32,
+        advance_scale: f32,
+        justify: bool,
+        tabular_figures: bool,
+        max_lines: Option<usize>,
+        ellipsis: bool,
+    ) -> Tracking {
+        Tracking {
+            layout,
+            tracking,
+            advance_scale,
+            justify,
+            tabular_figures,
+            max_lines,
+            ellipsis,
+        }
+    }
+
+    /// Drops the lines past the maximum amount of lines, returning the first
+    /// glyph dropped, if any.
+    fn truncate(&self, glyphs: &mut Vec<SectionGlyph>) -> Option<SectionGlyph> {
+        let end = lines(glyphs).get(self.max_lines?)?.start;
+        let dropped = glyphs[end].clone();
+
+        glyphs.truncate(end);
+
+        Some(dropped)
+    }
+
+    /// Gives every digit of the given lines the advance of the widest digit
+    /// of its font, centering it in the extra space.
+    fn tabulate<F: Font>(
+        &self,
+        fonts: &[F],
+        texts: &[SectionText<'_>],
+        glyphs: &mut [SectionGlyph],
+        lines: &[Range<usize>],
+        h_align: HorizontalAlign,
+    ) {
+        widen(glyphs, lines, h_align, |glyph| {
+            if !is_digit(texts, glyph) {
+                return 0.0;
+            }
+
+            let font = fonts[glyph.font_id.0].as_scaled(glyph.glyph.scale);
+            let widest = ('0'..='9')
+                .map(|digit| font.h_advance(font.glyph_id(digit)))
+                .fold(0.0, f32::max);
+
+            (widest - font.h_advance(glyph.glyph.id)).max(0.0)
+        });
+    }
+
+    /// Ends the last line with an ellipsis, removing glyphs from its end
+    /// until the ellipsis fits in the width of the bounds.
+    fn append_ellipsis<F: Font>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        texts: &[SectionText<'_>],
+        glyphs: &mut Vec<SectionGlyph>,
+        dropped: SectionGlyph,
+    ) {
+        let h_align = match self.layout {
+            Layout::SingleLine { h_align, .. }
+            | Layout::Wrap { h_align, .. } => h_align,
+        };
+
+        let line_start =
+            lines(glyphs).last().map_or(glyphs.len(), |line| line.start);
+
+        // The ellipsis continues the last line, or takes the place of the
+        // first glyph dropped when no line is kept
+        let reference = glyphs.last().unwrap_or(&dropped).clone();
+        let left = glyphs.get(line_start).unwrap_or(&dropped).glyph.position.x;
+        let baseline = reference.glyph.position.y;
+
+        let end_of = |glyph: &SectionGlyph| {
+            glyph.glyph.position.x
+                + fonts[glyph.font_id.0]
+                    .as_scaled(glyph.glyph.scale)
+                    .h_advance(glyph.glyph.id)
+                    * self.advance_scale
+        };
+
+        let font = fonts[reference.font_id.0].as_scaled(reference.glyph.scale);
+        let spacing = self.tracking * reference.glyph.scale.x;
+
+        // Fonts without an ellipsis get three periods instead
+        let (id, count) = match font.glyph_id('…') {
+            GlyphId(0) => (font.glyph_id('.'), 3),
+            id => (id, 1),
+        };
+
+        let advance = font.h_advance(id) * self.advance_scale + spacing;
+        let width = advance * count as f32 - spacing;
+
+        let width_bounds = geometry.bounds.0;
+
+        let limit = if width_bounds.is_finite() {
+            let start = match h_align {
+                HorizontalAlign::Left => geometry.screen_position.0,
+                HorizontalAlign::Center => {
+                    geometry.screen_position.0 - width_bounds / 2.0
+                }
+                HorizontalAlign::Right => {
+                    geometry.screen_position.0 - width_bounds
+                }
+            };
+
+            start + width_bounds
+        } else {
+            f32::INFINITY
+        };
+
+        let old_end = glyphs[line_start..].last().map_or(left, end_of);
+        let mut replaced = dropped;
+
+        while glyphs.len() > line_start {
+            let last = &glyphs[glyphs.len() - 1];
+
+            if end_of(last) + spacing + width <= limit {
+                break;
+            }
+
+            replaced = glyphs.pop().unwrap();
+        }
+
+        while glyphs.len() > line_start
+            && is_whitespace(texts, &glyphs[glyphs.len() - 1])
+        {
+            replaced = glyphs.pop().unwrap();
+        }
+
+        let mut x = glyphs[line_start..]
+            .last()
+            .map_or(left, |last| end_of(last) + spacing);
+
+        let new_end = x + width;
+
+        for _ in 0..count {
+            glyphs.push(SectionGlyph {
+                section_index: replaced.section_index,
+                byte_index: replaced.byte_index,
+                glyph: id.with_scale_and_position(
+                    reference.glyph.scale,
+                    point(x, baseline),
+                ),
+                font_id: reference.font_id,
+            });
+
+            x += advance;
+        }
+
+        // Lines keep their horizontal alignment
+        let shift = match h_align {
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => (old_end - new_end) / 2.0,
+            HorizontalAlign::Right => old_end - new_end,
+        };
+
+        for glyph in glyphs[line_start..].iter_mut() {
+            glyph.glyph.position.x += shift;
+        }
+    }
+
+    /// Stretches the whitespace of the given lines so they fill the width of
+    /// the bounds.
+    fn justify<F: Font>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        texts: &[SectionText<'_>],
+        glyphs: &mut [SectionGlyph],
+        lines: &[Range<usize>],
+    ) {
+        let h_align = match self.layout {
+            Layout::Wrap { h_align, .. } => h_align,
+            Layout::SingleLine { .. } => return,
+        };
+
+        let width = geometry.bounds.0;
+
+        if !width.is_finite() {
+            return;
+        }
+
+        let start = match h_align {
+            HorizontalAlign::Left => geometry.screen_position.0,
+            HorizontalAlign::Center => geometry.screen_position.0 - width / 2.0,
+            HorizontalAlign::Right => geometry.screen_position.0 - width,
+        };
+
+        for (index, line) in lines.iter().enumerate() {
+            // The last line of a paragraph is followed by a hard break, or by
+            // nothing at all
+            #[allow(unknown_lints, clippy::unnecessary_map_or)]
+            let is_last = lines.get(index + 1).map_or(true, |next| {
+                hard_break_between(
+                    texts,
+                    &glyphs[line.end - 1],
+                    &glyphs[next.start],
+                )
+            });
+
+            if is_last {
+                continue;
+            }
+
+            let line = &mut glyphs[line.clone()];
+
+            // Whitespace at the edges of the line is not stretched
+            let first =
+                line.iter().position(|glyph| !is_whitespace(texts, glyph));
+            let last =
+                line.iter().rposition(|glyph| !is_whitespace(texts, glyph));
+
+            let (first, last) = match first.zip(last) {
+                Some(range) => range,
+                None => continue,
+            };
+
+            let gaps = line[first..last]
+                .iter()
+                .filter(|glyph| is_whitespace(texts, glyph))
+                .count();
+
+            if gaps == 0 {
+                continue;
+            }
+
+            let origin = line[first].glyph.position.x;
+            let content = line[last].glyph.position.x - origin
+                + fonts[line[last].font_id.0]
+                    .as_scaled(line[last].glyph.scale)
+                    .h_advance(line[last].glyph.id)
+                    * self.advance_scale;
+
+            let extra = (width - content) / gaps as f32;
+
+            if extra <= 0.0 {
+                continue;
+            }
+
+            let mut offset = start - origin;
+
+            for glyph in line[..first].iter_mut() {
+                glyph.glyph.position.x += offset;
+            }
+
+            for glyph in line[first..].iter_mut() {
+                glyph.glyph.position.x += offset;
+
+                if is_whitespace(texts, glyph) {
+                    offset += extra;
+                }
+            }
+        }
+    }
+}
+
+impl GlyphPositioner for Tracking {
+    fn calculate_glyphs<F, S>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[S],
+    ) -> Vec<SectionGlyph>
+    where
+        F: Font,
+        S: ToSectionText,
+    {
+        let mut glyphs =
+            self.layout.calculate_glyphs(fonts, geometry, sections);
+
+        let dropped = self.truncate(&mut glyphs);
+
+        if self.tracking == 0.0
+            && self.advance_scale == 1.0
+            && !self.justify
+            && !self.tabular_figures
+            && !(self.ellipsis && dropped.is_some())
+        {
+            return glyphs;
+        }
+
+        let h_align = match self.layout {
+            Layout::SingleLine { h_align, .. }
+            | Layout::Wrap { h_align, .. } => h_align,
+        };
+
+        let texts: Vec<_> = sections
+            .iter()
+            .map(ToSectionText::to_section_text)
+            .collect();
+
+        let lines = lines(&glyphs);
+
+        if self.tabular_figures {
+            self.tabulate(fonts, &texts, &mut glyphs, &lines, h_align);
+        }
+
+        for line in &lines {
+            let line = &mut glyphs[line.clone()];
+            let origin = line[0].glyph.position.x;
+            let mut offsets = Vec::with_capacity(line.len());
+            let mut spacing = 0.0;
+
+            for glyph in line.iter() {
+                let advance = (glyph.glyph.position.x - origin)
+                    * (self.advance_scale - 1.0);
+
+                offsets.push(spacing + advance);
+                spacing += self.tracking * glyph.glyph.scale.x;
+            }
+
+            // The space after the last glyph is not part of the line, but
+            // its scaled advance is
+            let width = offsets.last().copied().unwrap_or(0.0)
+                + line.last().map_or(0.0, |last| {
+                    fonts[last.font_id.0]
+                        .as_scaled(last.glyph.scale)
+                        .h_advance(last.glyph.id)
+                        * (self.advance_scale - 1.0)
+                });
+
+            let shift = match h_align {
+                HorizontalAlign::Left => 0.0,
+                HorizontalAlign::Center => -width / 2.0,
+                HorizontalAlign::Right => -width,
+            };
+
+            for (glyph, offset) in line.iter_mut().zip(offsets) {
+                glyph.glyph.position.x += offset + shift;
+            }
+        }
+
+        if self.justify {
+            self.justify(fonts, geometry, &texts, &mut glyphs, &lines);
+        }
+
+        if let Some(dropped) = dropped.filter(|_| self.ellipsis) {
+            self.append_ellipsis(fonts, geometry, &texts, &mut glyphs, dropped);
+        }
+
+        glyphs
+    }
+
+    fn bounds_rect(&self, geometry: &SectionGeometry) -> Rect {
+        self.layout.bounds_rect(geometry)
+    }
+}
+
+impl Hash for Tracking {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        self.tracking.to_bits().hash(state);
+        self.advance_scale.to_bits().hash(state);
+        self.justify.hash(state);
+        self.tabular_figures.hash(state);
+        self.max_lines.hash(state);
+        self.ellipsis.hash(state);
+    }
+}
+
+/// Returns the ranges of the consecutive glyphs sharing a baseline.
+fn lines(glyphs: &[SectionGlyph]) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+
+    while start < glyphs.len() {
+        let baseline = glyphs[start].glyph.position.y;
+
+        let end = glyphs[start..]
+            .iter()
+            .position(|glyph| glyph.glyph.position.y != baseline)
+            .map_or(glyphs.len(), |length| start + length);
+
+        lines.push(start..end);
+        start = end;
+    }
+
+    lines
+}
+
+#[allow(unknown_lints, clippy::unnecessary_map_or)]
+fn is_whitespace(texts: &[SectionText<'_>], glyph: &SectionGlyph) -> bool {
+    texts[glyph.section_index].text[glyph.byte_index..]
+        .chars()
+        .next()
+        .map_or(false, char::is_whitespace)
+}
+
+#[allow(unknown_lints, clippy::unnecessary_map_or)]
+fn is_digit(texts: &[SectionText<'_>], glyph: &SectionGlyph) -> bool {
+    texts[glyph.section_index].text[glyph.byte_index..]
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_ascii_digit())
+}
+
+/// Returns whether there is a hard line break in the text between the two
+/// glyphs.
+fn hard_break_between(
+    texts: &[SectionText<'_>],
+    from: &SectionGlyph,
+    to: &SectionGlyph,
+) -> bool {
+    (from.section_index..=to.section_index).any(|index| {
+        let text = texts[index].text;
+        let start = if index == from.section_index {
+            from.byte_index
+        } else {
+            0
+        };
+        let end = if index == to.section_index {
+            to.byte_index
+        } else {
+            text.len()
+        };
+
+        text[start..end].contains('\n')
+    })
+}
+
+/// Adds the extra advance returned for every glyph of the given lines,
+/// centering the glyph in it and shifting the lines to keep their alignment.
+fn widen(
+    glyphs: &mut [SectionGlyph],
+    lines: &[Range<usize>],
+    h_align: HorizontalAlign,
+    extra: impl Fn(&SectionGlyph) -> f32,
+) {
+    for line in lines {
+        let line = &mut glyphs[line.clone()];
+        let mut offset = 0.0;
+
+        for glyph in line.iter_mut() {
+            let extra = extra(glyph);
+
+            glyph.glyph.position.x += offset + extra / 2.0;
+            offset += extra;
+        }
+
+        let shift = match h_align {
+            HorizontalAlign::Left => 0.0,
+            HorizontalAlign::Center => -offset / 2.0,
+            HorizontalAlign::Right => -offset,
+        };
+
+        for glyph in line.iter_mut() {
+            glyph.glyph.position.x += shift;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glyph_brush::ab_glyph::FontRef;
+    use glyph_brush::FontId;
+
+    const SCALE: f32 = 20.0;
+
+    fn font() -> FontRef<'static> {
+        FontRef::try_from_slice(include_bytes!(
+            "../examples/Inconsolata-Regular.ttf"
+        ))
+        .unwrap()
+    }
+
+    fn layout(
+        tracking: Tracking,
+        text: &str,
+        width: f32,
+    ) -> (FontRef<'static>, Vec<SectionGlyph>) {
+        let font = font();
+        let geometry = SectionGeometry {
+            screen_position: (0.0, 0.0),
+            bounds: (width, f32::INFINITY),
+        };
+        let texts = [SectionText {
+            text,
+            scale: SCALE.into(),
+            font_id: FontId(0),
+        }];
+
+        let glyphs = tracking.calculate_glyphs(
+            std::slice::from_ref(&font),
+            &geometry,
+            &texts,
+        );
+
+        (font, glyphs)
+    }
+
+    fn truncating(max_lines: usize) -> Tracking {
+        Tracking::new(
+            Layout::default_wrap(),
+            0.0,
+            1.0,
+            false,
+            false,
+            Some(max_lines),
+            true,
+        )
+    }
+
+    /// Returns the text drawn by the glyphs, with `…` for the ellipsis.
+    fn drawn(
+        font: &FontRef<'_>,
+        text: &str,
+        glyphs: &[SectionGlyph],
+    ) -> String {
+        let ellipsis = font.glyph_id('…');
+
+        glyphs
+            .iter()
+            .map(|glyph| {
+                if glyph.glyph.id == ellipsis {
+                    '…'
+                } else {
+                    text[glyph.byte_index..].chars().next().unwrap()
+                }
+            })
+            .collect()
+    }
+
+    fn advance(font: &FontRef<'_>) -> f32 {
+        font.as_scaled(SCALE).h_advance(font.glyph_id('a'))
+    }
+
+    #[test]
+    fn drops_lines_past_the_maximum() {
+        let text = "aaaa bbbb cccc";
+        let width = advance(&font()) * 5.0;
+
+        let tracking = Tracking::new(
+            Layout::default_wrap(),
+            0.0,
+            1.0,
+            false,
+            false,
+            Some(2),
+            false,
+        );
+        let (font, glyphs) = layout(tracking, text, width);
+
+        assert_eq!(drawn(&font, text, &glyphs).trim_end(), "aaaa bbbb");
+    }
+
+    #[test]
+    fn ellipsis_replaces_the_whitespace_at_a_boundary() {
+        let text = "aaaa bbbb";
+        let (font, glyphs) =
+            layout(truncating(1), text, advance(&font()) * 5.0);
+
+        assert_eq!(drawn(&font, text, &glyphs), "aaaa…");
+    }
+
+    #[test]
+    fn ellipsis_breaks_in_the_middle_of_a_word_to_fit() {
+        let text = "aaaa bbbb";
+        let width = advance(&font()) * 4.5;
+        let (font, glyphs) = layout(truncating(1), text, width);
+
+        assert_eq!(drawn(&font, text, &glyphs), "aaa…");
+
+        let last = glyphs.last().unwrap();
+        let right = last.glyph.position.x
+            + font.as_scaled(SCALE).h_advance(last.glyph.id);
+
+        assert!(right <= width);
+    }
+
+    #[test]
+    fn ellipsis_wider_than_the_bounds_replaces_the_line() {
+        let text = "aaaa bbbb";
+        let (font, glyphs) =
+            layout(truncating(1), text, advance(&font()) / 2.0);
+
+        assert_eq!(drawn(&font, text, &glyphs), "…");
+    }
+
+    #[test]
+    fn text_fitting_the_maximum_has_no_ellipsis() {
+        let text = "aaaa bbbb";
+        let (font, glyphs) =
+            layout(truncating(2), text, advance(&font()) * 5.0);
+
+        assert_eq!(drawn(&font, text, &glyphs), "aaaa bbbb");
+    }
+
+    fn tabular() -> Tracking {
+        Tracking::new(
+            Layout::default_wrap(),
+            0.0,
+            1.0,
+            false,
+            true,
+            None,
+            false,
+        )
+    }
+
+    #[test]
+    fn tabular_figures_keep_the_advance_of_other_characters() {
+        let text = "ab.,;:";
+        let plain = Tracking::new(
+            Layout::default_wrap(),
+            0.0,
+            1.0,
+            false,
+            false,
+            None,
+            false,
+        );
+
+        let (_, tabular) = layout(tabular(), text, f32::INFINITY);
+        let (_, plain) = layout(plain, text, f32::INFINITY);
+
+        assert_eq!(tabular, plain);
+    }
+
+    /// The advance of the widest digit of the synthetic font.
+    const WIDEST: f32 = 10.0;
+
+    /// Returns the advance of the character in a synthetic font with
+    /// proportional digits, where `1` is narrower than the other digits.
+    fn proportional_advance(c: char) -> f32 {
+        match c {
+            '1' => 4.0,
+            '0'..='9' => WIDEST,
+            _ => 3.0,
+        }
+    }
+
+    /// Lays out one line of glyphs per line of the text with the synthetic
+    /// font, widens its digits as `tabulate` does and returns the positions
+    /// of the glyphs of every line.
+    fn tabulated(text: &str, h_align: HorizontalAlign) -> Vec<Vec<f32>> {
+        let mut glyphs = Vec::new();
+
+        for (y, line) in text.split('\n').enumerate() {
+            let offset = line.as_ptr() as usize - text.as_ptr() as usize;
+            let mut x = 0.0;
+
+            for (byte_index, c) in line.char_indices() {
+                glyphs.push(SectionGlyph {
+                    section_index: 0,
+                    byte_index: offset + byte_index,
+                    glyph: GlyphId(0).with_scale_and_position(
+                        SCALE,
+                        point(x, y as f32 * SCALE),
+                    ),
+                    font_id: FontId(0),
+                });
+                x += proportional_advance(c);
+            }
+        }
+
+        let lines = lines(&glyphs);
+
+        widen(&mut glyphs, &lines, h_align, |glyph| {
+            let c = text[glyph.byte_index..].chars().next().unwrap();
+
+            if c.is_ascii_digit() {
+                WIDEST - proportional_advance(c)
+            } else {
+                0.0
+            }
+        });
+
+        lines
+            .into_iter()
+            .map(|line| {
+                glyphs[line]
+                    .iter()
+                    .map(|glyph| glyph.glyph.position.x)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tabular_figures_line_up_digits_across_lines() {
+        let columns = tabulated("1.5\n8.25\n4.0", HorizontalAlign::Left);
+
+        // The digits are centered in their advance, so the glyphs following
+        // them line up
+        assert_eq!(columns[0][1..3], columns[1][1..3]);
+        assert_eq!(columns[0][1..3], columns[2][1..3]);
+    }
+
+    #[test]
+    fn tabular_figures_give_digits_the_widest_advance() {
+        let columns = tabulated("1811", HorizontalAlign::Left);
+        let pen: Vec<f32> = "1811"
+            .chars()
+            .zip(&columns[0])
+            .map(|(c, x)| x - (WIDEST - proportional_advance(c)) / 2.0)
+            .collect();
+
+        assert_eq!(pen, [0.0, WIDEST, WIDEST * 2.0, WIDEST * 3.0]);
+    }
+
+    #[test]
+    fn tabular_figures_keep_the_alignment_of_lines() {
+        let right = tabulated("1811", HorizontalAlign::Right);
+        let center = tabulated("1811", HorizontalAlign::Center);
+        let plain = 4.0 + WIDEST + 4.0 + 4.0;
+        let widened = WIDEST * 4.0;
+
+        // The pen ends where the line ends without tabular figures when
+        // aligned right, and the widening is split evenly when centered
+        assert_eq!(right[0][3] - 3.0 + WIDEST, plain);
+        assert_eq!(center[0][0] - 3.0, -(widened - plain) / 2.0);
+    }
+}
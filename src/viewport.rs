@@ -0,0 +1,16 @@
+/// A rectangle of the render target that the text is drawn into, in physical
+/// pixels from its top-left corner.
+///
+/// Unlike a scissoring [`Region`](struct.Region.html), which only clips what
+/// is drawn, a viewport maps the whole output of the transform into the
+/// rectangle, squeezing or stretching it. Depths are mapped from `0.0` and
+/// `1.0` into `min_depth` and `max_depth`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub min_depth: f32,
+    pub max_depth: f32,
+}
@@ -0,0 +1,650 @@
+#![cfg(feature = "test_support")]
+
+use wgpu_glyph::test_support::Headless;
+use wgpu_glyph::{
+    ab_glyph, CustomShader, DrawResult, GlyphBrush, GlyphBrushBuilder, Region,
+    Section, Text,
+};
+
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+const WIDTH: u32 = 128;
+const HEIGHT: u32 = 64;
+
+/// Returns a headless device.
+///
+/// The tests using it are ignored by default, since they need an adapter.
+/// Run them with `cargo test --features test_support -- --ignored`.
+fn headless() -> Headless {
+    Headless::new().expect("no device is available")
+}
+
+/// Creates a render target that can be read back.
+fn target(headless: &Headless) -> wgpu::Texture {
+    headless.device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width: WIDTH,
+            height: HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC,
+    })
+}
+
+fn glyph_brush(headless: &Headless) -> GlyphBrush<()> {
+    let font = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "../examples/Inconsolata-Regular.ttf"
+    ))
+    .unwrap();
+
+    GlyphBrushBuilder::using_font(font).build(&headless.device, FORMAT)
+}
+
+fn section(text: &str, position: (f32, f32)) -> Section<'_> {
+    Section {
+        screen_position: position,
+        text: vec![Text::new(text)
+            .with_color([1.0, 1.0, 1.0, 1.0])
+            .with_scale(24.0)],
+        ..Section::default()
+    }
+}
+
+/// Returns the columns with any coverage, given the pixels of a render.
+fn covered_columns(pixels: &[u8]) -> Vec<u32> {
+    (0..WIDTH)
+        .filter(|x| {
+            (0..HEIGHT).any(|y| pixels[((y * WIDTH + x) * 4 + 3) as usize] > 0)
+        })
+        .collect()
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn renders_glyphs() {
+    let headless = headless();
+
+    let mut glyph_brush = glyph_brush(&headless);
+    glyph_brush.queue(section("Hello", (10.0, 10.0)));
+
+    let pixels = headless
+        .render(&mut glyph_brush, FORMAT, WIDTH, HEIGHT)
+        .unwrap();
+
+    assert!(!covered_columns(&pixels).is_empty());
+}
+
+/// Returns the highest alpha of every cell of `count` glyphs laid out from
+/// the given left position, given the pixels of a render.
+fn glyph_alphas(pixels: &[u8], left: u32, advance: u32, count: u32) -> Vec<u8> {
+    (0..count)
+        .map(|i| {
+            (left + i * advance..left + (i + 1) * advance)
+                .flat_map(|x| {
+                    (0..HEIGHT).map(move |y| {
+                        pixels[((y * WIDTH + x) * 4 + 3) as usize]
+                    })
+                })
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn fades_glyphs_in_one_after_another() {
+    let headless = headless();
+
+    let mut glyph_brush = glyph_brush(&headless);
+
+    let mut previous = vec![0; 4];
+
+    for step in 0..=10 {
+        let progress = step as f32 * 0.5;
+        glyph_brush.set_reveal_progress(Some(progress));
+
+        // Rectangles are drawn before the glyphs, and must not delay the fade
+        glyph_brush.queue_rect(
+            ab_glyph::Rect {
+                min: ab_glyph::point(0.0, 0.0),
+                max: ab_glyph::point(4.0, 4.0),
+            },
+            0.0,
+            [0.0, 0.0, 0.0, 1.0],
+        );
+        glyph_brush.queue(section("MMMM", (10.0, 10.0)));
+
+        let pixels = headless
+            .render(&mut glyph_brush, FORMAT, WIDTH, HEIGHT)
+            .unwrap();
+
+        // Inconsolata advances 12 pixels at a scale of 24
+        let alphas = glyph_alphas(&pixels, 10, 12, 4);
+
+        for (glyph, (&alpha, &before)) in
+            alphas.iter().zip(&previous).enumerate()
+        {
+            assert!(
+                alpha >= before,
+                "glyph {} faded out from {} to {} at progress {}",
+                glyph,
+                before,
+                alpha,
+                progress
+            );
+        }
+
+        assert!(
+            alphas.windows(2).all(|pair| pair[0] >= pair[1]),
+            "later glyphs are more opaque at progress {}: {:?}",
+            progress,
+            alphas
+        );
+
+        previous = alphas;
+    }
+
+    assert!(previous.iter().all(|&alpha| alpha > 200), "{:?}", previous);
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn draws_over_max_instances_fail() {
+    let headless = headless();
+
+    let font = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "../examples/Inconsolata-Regular.ttf"
+    ))
+    .unwrap();
+
+    let mut glyph_brush = GlyphBrushBuilder::using_font(font)
+        .max_instances(3)
+        .build(&headless.device, FORMAT);
+
+    glyph_brush.queue(section("Hi", (10.0, 10.0)));
+
+    let pixels = headless
+        .render(&mut glyph_brush, FORMAT, WIDTH, HEIGHT)
+        .unwrap();
+
+    assert!(!covered_columns(&pixels).is_empty());
+
+    glyph_brush.queue(section("Hello", (10.0, 10.0)));
+
+    let error = headless
+        .render(&mut glyph_brush, FORMAT, WIDTH, HEIGHT)
+        .unwrap_err();
+
+    assert!(error.contains("maximum of 3"), "{}", error);
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn try_build_returns_validation_errors() {
+    let headless = headless();
+
+    let font = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "../examples/Inconsolata-Regular.ttf"
+    ))
+    .unwrap();
+
+    let valid = GlyphBrushBuilder::using_font(font.clone())
+        .try_build(&headless.device, FORMAT);
+
+    assert!(valid.is_ok());
+
+    let missing_entry_point = GlyphBrushBuilder::using_font(font.clone())
+        .shader(
+            CustomShader::new(include_str!("../src/shader/glyph.wgsl"))
+                .with_vertex_entry_point("vs_main")
+                .with_fragment_entry_point("missing"),
+        )
+        .try_build(&headless.device, FORMAT);
+
+    assert!(missing_entry_point.is_err());
+
+    let cache_format = GlyphBrushBuilder::using_font(font)
+        .cache_format(wgpu::TextureFormat::Rgba8Unorm)
+        .try_build(&headless.device, FORMAT);
+
+    assert!(cache_format.is_err());
+}
+
+/// Draws the queued sections with the given transform and scissoring region,
+/// and reads the pixels of the render back.
+fn render_scissored(
+    headless: &Headless,
+    glyph_brush: &mut GlyphBrush<()>,
+    transform: [f32; 16],
+    region: Region,
+) -> Vec<u8> {
+    let texture = target(headless);
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut encoder = headless
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+    let _ = glyph_brush
+        .draw_queued_with_transform_and_scissoring(
+            &headless.device,
+            &mut staging_belt,
+            &mut encoder,
+            &view,
+            transform,
+            region,
+        )
+        .unwrap();
+
+    staging_belt.finish();
+    headless.queue.submit(Some(encoder.finish()));
+
+    headless.read_texture(&texture, WIDTH, HEIGHT).unwrap()
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn scrolls_glyphs_within_the_viewport() {
+    let headless = headless();
+
+    const SCROLL: u32 = 16;
+
+    let everything = Region {
+        x: 0,
+        y: 0,
+        width: WIDTH,
+        height: HEIGHT,
+    };
+
+    let viewport = Region {
+        x: 32,
+        y: 0,
+        width: 64,
+        height: HEIGHT,
+    };
+
+    let projection = wgpu_glyph::orthographic_projection(WIDTH, HEIGHT);
+
+    // Scrolls the content to the left
+    let mut scrolled = projection;
+    scrolled[12] -= 2.0 * SCROLL as f32 / WIDTH as f32;
+
+    let mut glyph_brush = glyph_brush(&headless);
+
+    glyph_brush.queue(section("MMMMMMMM", (8.0, 10.0)));
+    let still =
+        render_scissored(&headless, &mut glyph_brush, projection, everything);
+
+    glyph_brush.queue(section("MMMMMMMM", (8.0, 10.0)));
+    let moved =
+        render_scissored(&headless, &mut glyph_brush, scrolled, everything);
+
+    glyph_brush.queue(section("MMMMMMMM", (8.0, 10.0)));
+    let clipped =
+        render_scissored(&headless, &mut glyph_brush, scrolled, viewport);
+
+    let still = covered_columns(&still);
+    let moved = covered_columns(&moved);
+    let clipped = covered_columns(&clipped);
+
+    // Visible glyphs shift by the scroll offset
+    let shifted: Vec<u32> = still
+        .iter()
+        .filter(|&&x| x >= SCROLL)
+        .map(|x| x - SCROLL)
+        .collect();
+
+    assert_eq!(moved, shifted);
+
+    // Glyphs outside of the viewport are clipped
+    assert!(!clipped.is_empty());
+    assert!(clipped
+        .iter()
+        .all(|&x| x >= viewport.x && x < viewport.x + viewport.width));
+
+    let visible: Vec<u32> = moved
+        .iter()
+        .copied()
+        .filter(|&x| x >= viewport.x && x < viewport.x + viewport.width)
+        .collect();
+
+    assert_eq!(clipped, visible);
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn drawing_instances_keeps_the_changes_of_the_queued_draw() {
+    let headless = headless();
+
+    let mut glyph_brush = glyph_brush(&headless);
+
+    for _ in 0..2 {
+        glyph_brush.queue(section("Hello", (10.0, 10.0)));
+
+        let _ = headless
+            .render(&mut glyph_brush, FORMAT, WIDTH, HEIGHT)
+            .unwrap();
+    }
+
+    assert!(!glyph_brush.needs_redraw());
+
+    let texture = target(&headless);
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut encoder = headless
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+    let rect = ab_glyph::Rect {
+        min: ab_glyph::point(0.0, 0.0),
+        max: ab_glyph::point(8.0, 8.0),
+    };
+
+    glyph_brush
+        .draw_instances(
+            &headless.device,
+            &mut staging_belt,
+            &mut encoder,
+            &view,
+            wgpu_glyph::orthographic_projection(WIDTH, HEIGHT),
+            &[wgpu_glyph::Instance::new(rect, rect, 0.0, [1.0; 4])],
+        )
+        .unwrap();
+
+    staging_belt.finish();
+    headless.queue.submit(Some(encoder.finish()));
+
+    assert!(!glyph_brush.needs_redraw());
+}
+
+/// Draws the queued sections onto a new texture, returning the result of the
+/// draw without reading the texture back.
+fn draw(headless: &Headless, glyph_brush: &mut GlyphBrush<()>) -> DrawResult {
+    let texture = target(headless);
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut encoder = headless
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+    let result = glyph_brush
+        .draw_queued(
+            &headless.device,
+            &mut staging_belt,
+            &mut encoder,
+            &view,
+            WIDTH,
+            HEIGHT,
+        )
+        .unwrap();
+
+    staging_belt.finish();
+    headless.queue.submit(Some(encoder.finish()));
+
+    result
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn uploads_glyphs_within_the_upload_budget() {
+    let headless = headless();
+
+    const BUDGET: usize = 4;
+    const TEXT: &str = "abcdefghij\nklmnopqrst";
+
+    let font = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "../examples/Inconsolata-Regular.ttf"
+    ))
+    .unwrap();
+
+    let mut budgeted_brush = GlyphBrushBuilder::using_font(font)
+        .upload_budget(BUDGET)
+        .build(&headless.device, FORMAT);
+
+    let mut frames = 0;
+    let mut uploaded = 0;
+
+    loop {
+        budgeted_brush.queue(section(TEXT, (4.0, 4.0)));
+
+        let result = draw(&headless, &mut budgeted_brush);
+
+        assert!(
+            result.glyphs_uploaded <= BUDGET,
+            "{} glyphs uploaded in frame {}",
+            result.glyphs_uploaded,
+            frames
+        );
+
+        uploaded += result.glyphs_uploaded;
+        frames += 1;
+
+        if !budgeted_brush.needs_redraw() {
+            break;
+        }
+
+        assert!(frames < 100, "the glyphs are never all uploaded");
+    }
+
+    // Every glyph is uploaded once, spread over several frames
+    assert_eq!(uploaded, 20);
+    assert!(frames > 20 / BUDGET);
+
+    // The frames drawn once all the glyphs are uploaded are complete
+    budgeted_brush.queue(section(TEXT, (4.0, 4.0)));
+    let budgeted = headless
+        .render(&mut budgeted_brush, FORMAT, WIDTH, HEIGHT)
+        .unwrap();
+
+    let mut unbudgeted = glyph_brush(&headless);
+    unbudgeted.queue(section(TEXT, (4.0, 4.0)));
+    let expected = headless
+        .render(&mut unbudgeted, FORMAT, WIDTH, HEIGHT)
+        .unwrap();
+
+    assert_eq!(budgeted, expected);
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn redraws_retained_sections_after_clearing_the_cache() {
+    let headless = headless();
+
+    let mut glyph_brush = glyph_brush(&headless);
+    let _ = glyph_brush.retain(section("Hello", (10.0, 10.0)).to_owned());
+
+    let drawn = headless
+        .render(&mut glyph_brush, FORMAT, WIDTH, HEIGHT)
+        .unwrap();
+
+    let generation = glyph_brush.cache_generation();
+    glyph_brush.clear_cache(&headless.device);
+    assert_ne!(glyph_brush.cache_generation(), generation);
+
+    let texture = target(&headless);
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut encoder = headless
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+    glyph_brush
+        .redraw(&headless.device, &mut staging_belt, &mut encoder, &view)
+        .unwrap();
+
+    staging_belt.finish();
+    headless.queue.submit(Some(encoder.finish()));
+
+    let redrawn = headless.read_texture(&texture, WIDTH, HEIGHT).unwrap();
+
+    assert!(!covered_columns(&redrawn).is_empty());
+    assert_eq!(redrawn, drawn);
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn draws_every_transform_with_a_clear_load_op() {
+    let headless = headless();
+
+    let font = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "../examples/Inconsolata-Regular.ttf"
+    ))
+    .unwrap();
+
+    let mut glyph_brush = GlyphBrushBuilder::using_font(font)
+        .load_op(wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT))
+        .build(&headless.device, FORMAT);
+
+    let left = wgpu_glyph::orthographic_projection(WIDTH, HEIGHT);
+
+    // Moves the text to the right half of the target
+    let mut right = left;
+    right[12] += 1.0;
+
+    let texture = target(&headless);
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut encoder = headless
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+    glyph_brush.queue(section("Hi", (4.0, 10.0)));
+
+    let _ = glyph_brush
+        .draw_queued_with_transforms(
+            &headless.device,
+            &mut staging_belt,
+            &mut encoder,
+            &view,
+            &[left, right],
+        )
+        .unwrap();
+
+    staging_belt.finish();
+    headless.queue.submit(Some(encoder.finish()));
+
+    let columns = covered_columns(
+        &headless.read_texture(&texture, WIDTH, HEIGHT).unwrap(),
+    );
+
+    assert!(columns.iter().any(|&x| x < WIDTH / 2), "{:?}", columns);
+    assert!(columns.iter().any(|&x| x >= WIDTH / 2), "{:?}", columns);
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn renders_sections_without_a_color_with_the_default_color() {
+    let headless = headless();
+
+    let font = ab_glyph::FontArc::try_from_slice(include_bytes!(
+        "../examples/Inconsolata-Regular.ttf"
+    ))
+    .unwrap();
+    let mut glyph_brush = GlyphBrushBuilder::using_font(font)
+        .default_color([1.0, 0.0, 0.0, 1.0])
+        .build(&headless.device, FORMAT);
+
+    glyph_brush.queue_with_default_color(Section {
+        screen_position: (10.0, 10.0),
+        text: vec![Text::new("Hello").with_scale(24.0)],
+        ..Section::default()
+    });
+
+    let pixels = headless
+        .render(&mut glyph_brush, FORMAT, WIDTH, HEIGHT)
+        .unwrap();
+
+    let covered = pixels.chunks(4).max_by_key(|pixel| pixel[3]).unwrap();
+
+    assert!(covered[3] > 0);
+    assert!(covered[0] > 0);
+    assert_eq!(covered[1..3], [0, 0]);
+}
+
+/// Redraws the glyphs last drawn by the `glyph_brush` onto the `texture`,
+/// returning the pixels of the texture.
+fn redraw(
+    headless: &Headless,
+    glyph_brush: &mut GlyphBrush<()>,
+    texture: &wgpu::Texture,
+) -> Vec<u8> {
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut encoder = headless
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+    glyph_brush
+        .redraw(&headless.device, &mut staging_belt, &mut encoder, &view)
+        .unwrap();
+
+    staging_belt.finish();
+    headless.queue.submit(Some(encoder.finish()));
+
+    headless.read_texture(texture, WIDTH, HEIGHT).unwrap()
+}
+
+#[test]
+#[ignore = "requires a GPU adapter"]
+fn redraws_with_the_configured_load_op() {
+    let headless = headless();
+
+    let mut glyph_brush = glyph_brush(&headless);
+    let texture = target(&headless);
+
+    // The corner of the target is away from the text
+    let corner = |pixels: &[u8]| pixels[pixels.len() - 4..].to_vec();
+
+    // The text is white, and the clear colors have no green
+    let has_text = |pixels: &[u8]| pixels.chunks(4).any(|pixel| pixel[1] > 0);
+
+    glyph_brush.queue(section("Hi", (10.0, 10.0)));
+    glyph_brush.set_load_op(wgpu::LoadOp::Clear(wgpu::Color::RED));
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut staging_belt = wgpu::util::StagingBelt::new(1024);
+    let mut encoder = headless
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+    let _ = glyph_brush
+        .draw_queued(
+            &headless.device,
+            &mut staging_belt,
+            &mut encoder,
+            &view,
+            WIDTH,
+            HEIGHT,
+        )
+        .unwrap();
+
+    staging_belt.finish();
+    headless.queue.submit(Some(encoder.finish()));
+
+    let cleared = headless.read_texture(&texture, WIDTH, HEIGHT).unwrap();
+    assert_eq!(corner(&cleared), [255, 0, 0, 255]);
+
+    // Loading keeps the contents of the target
+    glyph_brush.set_load_op(wgpu::LoadOp::Load);
+
+    let loaded = redraw(&headless, &mut glyph_brush, &texture);
+    assert_eq!(corner(&loaded), [255, 0, 0, 255]);
+    assert!(has_text(&loaded));
+
+    // Clearing replaces them with the clear color
+    glyph_brush.set_load_op(wgpu::LoadOp::Clear(wgpu::Color::BLUE));
+
+    let recleared = redraw(&headless, &mut glyph_brush, &texture);
+    assert_eq!(corner(&recleared), [0, 0, 255, 255]);
+    assert!(has_text(&recleared));
+}